@@ -0,0 +1,525 @@
+//! Site configuration for Bloggo
+//!
+//! The configuration is read from an optional `bloggo.toml` file at the top
+//! of the source directory. Every setting has a default value, so the file
+//! only needs to contain the settings that differ from the defaults.
+
+use crate::{
+    error::Error,
+    highlight, markdown,
+    value::{Number, Value},
+    Result,
+};
+use chrono_tz::Tz;
+use pulldown_cmark::Options;
+use std::{ffi::OsString, path::Path};
+
+/// The name of the configuration file in the source directory.
+pub const CONFIG_FILE: &str = "bloggo.toml";
+
+/// Site-wide settings that control how posts are parsed and rendered.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// The time zone used for dates that do not specify an offset.
+    pub timezone: Tz,
+
+    /// The marker in the source of a post that separates the abstract from
+    /// the rest of the text.
+    pub excerpt_separator: String,
+
+    /// The maximum number of words in the plain text summary of a post.
+    pub summary_words: usize,
+
+    /// The reading speed used to estimate the reading time of a post.
+    pub words_per_minute: usize,
+
+    /// The maximum number of related posts listed for each post.
+    pub related_posts: usize,
+
+    /// The maximum number of recent posts listed for each tag.
+    pub recent_posts: usize,
+
+    /// The name of the theme used to generate the stylesheet for highlighted
+    /// code.
+    pub highlight_theme: String,
+
+    /// The Markdown extensions enabled for every post, unless a post
+    /// enables or disables them in its own front matter.
+    pub markdown_options: Options,
+
+    /// The collections of posts in the site, which always include the
+    /// `posts` and `pages` collections.
+    pub collections: Vec<Collection>,
+
+    /// The taxonomies by which the posts in the site are classified, which
+    /// always include the `tags` taxonomy.
+    pub taxonomies: Vec<Taxonomy>,
+}
+
+/// The order of the posts in a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Newest first.
+    Date,
+    /// Alphabetically by title.
+    Title,
+    /// By the `weight` property of each post, and then by title.
+    Weight,
+}
+
+/// A collection of posts, such as talks or projects, that are parsed from a
+/// directory in the source directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    /// The name of the collection, by which its posts are available to
+    /// templates.
+    pub name: String,
+
+    /// The directory in the source directory that contains the posts.
+    pub source_dir: String,
+
+    /// The directory in the destination directory to which the posts are
+    /// rendered, which is empty for the top of the destination directory.
+    pub output_dir: String,
+
+    /// The template used to render each post, unless a post specifies its
+    /// own layout. The `post` template is used if it does not exist.
+    pub layout: String,
+
+    /// The order of the posts in the collection.
+    pub sort: SortOrder,
+
+    /// Whether an index of the collection is rendered to `index.html` in the
+    /// output directory.
+    pub index: bool,
+
+    /// Whether an Atom feed of the collection is rendered to `atom.xml` in
+    /// the output directory.
+    pub feed: bool,
+}
+
+impl Collection {
+    /// Create a collection with the given name, whose posts are read from
+    /// and rendered to a directory of the same name, newest first, with an
+    /// index but without a feed.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            source_dir: String::from(name),
+            output_dir: String::from(name),
+            layout: String::from("post"),
+            sort: SortOrder::Date,
+            index: true,
+            feed: false,
+        }
+    }
+
+    /// Update the collection from a table of settings, such as
+    /// `{ layout = "talk", sort = "title", feed = true }`.
+    fn update(&mut self, settings: &Value) -> std::result::Result<(), String> {
+        let map = match settings {
+            Value::Map(map) => map,
+            _ => return Err(String::from("not a table")),
+        };
+        for (key, value) in map {
+            let string = || value.as_string().ok_or(format!("{}: not a string", key));
+            let boolean = || value.as_bool().ok_or(format!("{}: not a boolean", key));
+            match key.as_str() {
+                "source_dir" => self.source_dir = string()?,
+                "output_dir" => self.output_dir = string()?,
+                "layout" => self.layout = string()?,
+                "sort" => {
+                    self.sort = match string()?.as_str() {
+                        "date" => SortOrder::Date,
+                        "title" => SortOrder::Title,
+                        "weight" => SortOrder::Weight,
+                        s => return Err(format!("sort: unknown order {}", s)),
+                    }
+                }
+                "index" => self.index = boolean()?,
+                "feed" => self.feed = boolean()?,
+                _ => return Err(format!("unknown setting {}", key)),
+            }
+        }
+        if self.source_dir.is_empty() {
+            return Err(String::from("source_dir: is empty"));
+        }
+        Ok(())
+    }
+}
+
+/// A way of classifying posts, such as categories or authors, by the terms
+/// in a property of their front matter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Taxonomy {
+    /// The name of the taxonomy, by which its terms are available to
+    /// templates.
+    pub name: String,
+
+    /// The front matter property that contains the terms of each post, as a
+    /// string or an array of strings.
+    pub key: String,
+
+    /// The directory in the destination directory to which the index of
+    /// each term is rendered, which is empty for the top of the destination
+    /// directory.
+    pub output_dir: String,
+
+    /// The template used to render the index of each term. The `index`
+    /// template is used if it does not exist.
+    pub template: String,
+
+    /// Whether an Atom feed of each term is rendered next to its index.
+    pub feed: bool,
+
+    /// Whether an index of all of the terms is rendered to `index.html` in
+    /// the output directory, or in a directory named after the taxonomy if
+    /// the output directory is the top of the destination directory.
+    pub overview: bool,
+}
+
+impl Taxonomy {
+    /// Create a taxonomy with the given name, whose terms are read from the
+    /// property of the same name and whose indexes are rendered to a
+    /// directory of the same name using the `index` template, without feeds
+    /// or an index of all of the terms.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: String::from(name),
+            key: String::from(name),
+            output_dir: String::from(name),
+            template: String::from("index"),
+            feed: false,
+            overview: false,
+        }
+    }
+
+    /// Get the directory to which the index of all of the terms is rendered,
+    /// if there is one.
+    pub fn overview_dir(&self) -> Option<&str> {
+        match (self.overview, self.output_dir.is_empty()) {
+            (false, _) => None,
+            (true, true) => Some(&self.name),
+            (true, false) => Some(&self.output_dir),
+        }
+    }
+
+    /// Update the taxonomy from a table of settings, such as
+    /// `{ key = "category", template = "category", feed = true }`.
+    fn update(&mut self, settings: &Value) -> std::result::Result<(), String> {
+        let map = match settings {
+            Value::Map(map) => map,
+            _ => return Err(String::from("not a table")),
+        };
+        for (key, value) in map {
+            let string = || value.as_string().ok_or(format!("{}: not a string", key));
+            let boolean = || value.as_bool().ok_or(format!("{}: not a boolean", key));
+            match key.as_str() {
+                "key" => self.key = string()?,
+                "output_dir" => self.output_dir = string()?,
+                "template" => self.template = string()?,
+                "feed" => self.feed = boolean()?,
+                "overview" => self.overview = boolean()?,
+                _ => return Err(format!("unknown setting {}", key)),
+            }
+        }
+        if self.key.is_empty() {
+            return Err(String::from("key: is empty"));
+        }
+        if self.overview && self.template == self.name {
+            return Err(String::from("template: same as the index of all terms"));
+        }
+        Ok(())
+    }
+}
+
+impl Config {
+    /// Read the configuration from a TOML file.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Config> {
+        let p = path.as_ref();
+        let toml = std::fs::read_to_string(p)?;
+        Config::parse(&toml).map_err(|e| match e {
+            Error::InvalidConfig(_, s) => Error::InvalidConfig(p.into(), s),
+            e => e,
+        })
+    }
+
+    /// Parse the configuration from a TOML [str].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bloggo::config::Config;
+    ///
+    /// let config = Config::parse("timezone = \"Europe/London\"").unwrap();
+    /// assert_eq!(chrono_tz::Europe::London, config.timezone);
+    /// ```
+    pub fn parse(toml: &str) -> Result<Config> {
+        let invalid = |s: String| Error::InvalidConfig(OsString::from(CONFIG_FILE), s);
+
+        let tval = toml::from_str::<toml::Value>(toml).map_err(|e| invalid(e.to_string()))?;
+        let map = match Value::try_from(tval)? {
+            Value::Map(map) => map,
+            _ => return Err(invalid("not a table".to_string())),
+        };
+
+        let string = |key: &str| match map.get(key) {
+            None => Ok(None),
+            Some(Value::String(s)) => Ok(Some(s.clone())),
+            Some(_) => Err(invalid(format!("{}: not a string", key))),
+        };
+
+        let integer = |key: &str| match map.get(key) {
+            None => Ok(None),
+            Some(Value::Number(Number::Integer(i))) if *i >= 0 => Ok(Some(*i as usize)),
+            Some(_) => Err(invalid(format!("{}: not a non-negative integer", key))),
+        };
+
+        let mut config = Config::default();
+        if let Some(s) = string("timezone")? {
+            config.timezone = s.parse().map_err(|e| invalid(format!("timezone: {}", e)))?;
+        }
+        if let Some(s) = string("excerpt_separator")? {
+            if s.is_empty() {
+                return Err(invalid("excerpt_separator: is empty".to_string()));
+            }
+            config.excerpt_separator = s;
+        }
+        if let Some(i) = integer("summary_words")? {
+            config.summary_words = i;
+        }
+        if let Some(i) = integer("words_per_minute")? {
+            if i == 0 {
+                return Err(invalid("words_per_minute: is zero".to_string()));
+            }
+            config.words_per_minute = i;
+        }
+        if let Some(i) = integer("related_posts")? {
+            config.related_posts = i;
+        }
+        if let Some(i) = integer("recent_posts")? {
+            config.recent_posts = i;
+        }
+        if let Some(s) = string("highlight_theme")? {
+            if !highlight::theme_names().contains(&s) {
+                return Err(invalid(format!("highlight_theme: unknown theme {}", s)));
+            }
+            config.highlight_theme = s;
+        }
+        if let Some(v) = map.get("markdown") {
+            markdown::apply_extensions(&mut config.markdown_options, v)
+                .map_err(|e| invalid(format!("markdown: {}", e)))?;
+        }
+        match map.get("collections") {
+            None => {}
+            Some(Value::Map(collections)) => {
+                let mut names: Vec<&String> = collections.keys().collect();
+                names.sort();
+                for name in names {
+                    let i = match config.collections.iter().position(|c| c.name == *name) {
+                        Some(i) => i,
+                        None => {
+                            config.collections.push(Collection::new(name));
+                            config.collections.len() - 1
+                        }
+                    };
+                    config.collections[i]
+                        .update(&collections[name])
+                        .map_err(|e| invalid(format!("collections.{}: {}", name, e)))?;
+                }
+            }
+            Some(_) => return Err(invalid("collections: not a table".to_string())),
+        }
+        match map.get("taxonomies") {
+            None => {}
+            Some(Value::Map(taxonomies)) => {
+                let mut names: Vec<&String> = taxonomies.keys().collect();
+                names.sort();
+                for name in names {
+                    let i = match config.taxonomies.iter().position(|t| t.name == *name) {
+                        Some(i) => i,
+                        None => {
+                            config.taxonomies.push(Taxonomy::new(name));
+                            config.taxonomies.len() - 1
+                        }
+                    };
+                    config.taxonomies[i]
+                        .update(&taxonomies[name])
+                        .map_err(|e| invalid(format!("taxonomies.{}: {}", name, e)))?;
+                }
+            }
+            Some(_) => return Err(invalid("taxonomies: not a table".to_string())),
+        }
+        config.check_output_dirs().map_err(invalid)?;
+        Ok(config)
+    }
+
+    /// Check that the collections, the taxonomies and the series do not
+    /// render their indexes or feeds to the same directory. Collections can
+    /// share an output directory, such as the `posts` and `pages`
+    /// collections, as long as only one of them has an index or a feed.
+    fn check_output_dirs(&self) -> std::result::Result<(), String> {
+        for (i, c) in self.collections.iter().enumerate() {
+            if c.output_dir == "series" {
+                return Err(format!(
+                    "collections.{}: output_dir: reserved for series",
+                    c.name
+                ));
+            }
+            for other in &self.collections[..i] {
+                if c.output_dir == other.output_dir
+                    && (c.index && other.index || c.feed && other.feed)
+                {
+                    return Err(format!(
+                        "collections.{}: output_dir: same as collections.{}",
+                        c.name, other.name
+                    ));
+                }
+            }
+            if let Some(t) = self.taxonomies.iter().find(|t| {
+                !t.output_dir.is_empty() && t.output_dir == c.output_dir
+                    || t.overview_dir() == Some(c.output_dir.as_str())
+            }) {
+                return Err(format!(
+                    "collections.{}: output_dir: same as taxonomies.{}",
+                    c.name, t.name
+                ));
+            }
+        }
+        for (i, t) in self.taxonomies.iter().enumerate() {
+            if t.output_dir == "series" {
+                return Err(format!(
+                    "taxonomies.{}: output_dir: reserved for series",
+                    t.name
+                ));
+            }
+            if t.overview_dir() == Some("series") {
+                return Err(format!(
+                    "taxonomies.{}: overview: reserved for series",
+                    t.name
+                ));
+            }
+            if let Some(other) = self.taxonomies[..i]
+                .iter()
+                .find(|other| other.output_dir == t.output_dir)
+            {
+                return Err(format!(
+                    "taxonomies.{}: output_dir: same as taxonomies.{}",
+                    t.name, other.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            timezone: Tz::UTC,
+            excerpt_separator: String::from("<!--more-->"),
+            summary_words: 50,
+            words_per_minute: 200,
+            related_posts: 5,
+            recent_posts: 5,
+            highlight_theme: String::from("InspiredGitHub"),
+            markdown_options: markdown::default_options(),
+            collections: vec![
+                Collection {
+                    output_dir: String::new(),
+                    feed: true,
+                    ..Collection::new("posts")
+                },
+                Collection {
+                    output_dir: String::new(),
+                    layout: String::from("page"),
+                    sort: SortOrder::Weight,
+                    index: false,
+                    ..Collection::new("pages")
+                },
+            ],
+            taxonomies: vec![Taxonomy {
+                output_dir: String::new(),
+                feed: true,
+                overview: true,
+                ..Taxonomy::new("tags")
+            }],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_collections() {
+        let config = Config::parse(
+            "[collections.talks]\nsort = \"title\"\nfeed = true\n\n\
+             [collections.posts]\nlayout = \"article\"\n",
+        )
+        .unwrap();
+        let names: Vec<&str> = config.collections.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(vec!["posts", "pages", "talks"], names);
+        assert_eq!("article", config.collections[0].layout);
+        assert_eq!("", config.collections[0].output_dir);
+        assert_eq!(
+            Collection {
+                sort: SortOrder::Title,
+                feed: true,
+                ..Collection::new("talks")
+            },
+            config.collections[2]
+        );
+    }
+
+    #[test]
+    fn parse_collections_invalid() {
+        assert!(Config::parse("[collections.talks]\nsort = \"random\"\n").is_err());
+        assert!(Config::parse("[collections.talks]\nindex = \"yes\"\n").is_err());
+        assert!(Config::parse("[collections.talks]\ncolour = \"red\"\n").is_err());
+    }
+
+    #[test]
+    fn parse_collections_output_dirs() {
+        assert!(Config::parse("[collections.talks]\noutput_dir = \"\"\n").is_err());
+        assert!(Config::parse("[collections.talks]\noutput_dir = \"series\"\n").is_err());
+        assert!(Config::parse("[collections.talks]\noutput_dir = \"tags\"\n").is_err());
+        assert!(Config::parse(
+            "[collections.talks]\noutput_dir = \"topics\"\n\n\
+             [taxonomies.topics]\n"
+        )
+        .is_err());
+        assert!(Config::parse(
+            "[collections.talks]\noutput_dir = \"\"\nindex = false\n\n\
+             [taxonomies.topics]\noutput_dir = \"\"\n"
+        )
+        .is_err());
+        assert!(Config::parse("[collections.talks]\noutput_dir = \"\"\nindex = false\n").is_ok());
+    }
+
+    #[test]
+    fn parse_taxonomies() {
+        let config = Config::parse(
+            "[taxonomies.categories]\nkey = \"category\"\nfeed = true\n\n\
+             [taxonomies.tags]\ntemplate = \"tag\"\n",
+        )
+        .unwrap();
+        assert_eq!(2, config.taxonomies.len());
+        assert_eq!("tag", config.taxonomies[0].template);
+        assert_eq!("", config.taxonomies[0].output_dir);
+        assert_eq!(
+            Taxonomy {
+                key: String::from("category"),
+                feed: true,
+                ..Taxonomy::new("categories")
+            },
+            config.taxonomies[1]
+        );
+        assert!(Config::parse("[taxonomies.topics]\nkey = \"\"\n").is_err());
+        assert!(Config::parse("[taxonomies.tags]\ntemplate = \"tags\"\n").is_err());
+        assert!(
+            Config::parse("[collections.tags]\n\n[taxonomies.tags]\noverview = false\n").is_ok()
+        );
+    }
+}
@@ -22,6 +22,33 @@ pub enum Error {
     /// There was an unexpected End of File while parsing front matter.
     UnexpectedEOF(OsString),
 
+    /// The front matter of a post could not be parsed.
+    InvalidFrontMatter(OsString, String),
+
+    /// A date property in the front matter of a post could not be parsed.
+    InvalidDate(OsString, String),
+
+    /// A shortcode in a post, at the given line, could not be expanded.
+    InvalidShortcode(OsString, usize, String),
+
+    /// A post links to another post that does not exist.
+    BrokenLink(OsString, String),
+
+    /// The given number of links in the built site are broken.
+    BrokenLinks(usize),
+
+    /// A tag, or a term of another taxonomy, cannot be used in a path.
+    InvalidTag(String, String),
+
+    /// A series cannot be used in a path.
+    InvalidSeries(String, String),
+
+    /// Two files of the built site would be rendered to the same path.
+    DuplicateOutput(OsString, String),
+
+    /// The site configuration file could not be parsed.
+    InvalidConfig(OsString, String),
+
     /// Some other unspecfied error described in the message.
     Other(String),
 }
@@ -45,6 +72,32 @@ impl From<&Error> for String {
             Error::TemplateError(te) => format!("{}", te),
             Error::RenderError(re) => format!("{}", re),
             Error::UnexpectedEOF(s) => format!("Unexpected end of file: {}", s.to_string_lossy()),
+            Error::InvalidFrontMatter(p, s) => {
+                format!("Invalid front matter in {}: {}", p.to_string_lossy(), s)
+            }
+            Error::InvalidDate(p, s) => {
+                format!("Invalid date in {}: {}", p.to_string_lossy(), s)
+            }
+            Error::InvalidShortcode(p, line, s) => {
+                format!(
+                    "Invalid shortcode in {} at line {}: {}",
+                    p.to_string_lossy(),
+                    line,
+                    s
+                )
+            }
+            Error::BrokenLink(p, s) => {
+                format!("Broken link in {}: {}", p.to_string_lossy(), s)
+            }
+            Error::BrokenLinks(n) => format!("Broken links found: {}", n),
+            Error::InvalidTag(t, s) => format!("Invalid tag {}: {}", t, s),
+            Error::InvalidSeries(t, s) => format!("Invalid series {}: {}", t, s),
+            Error::DuplicateOutput(p, s) => {
+                format!("Duplicate output {}: {}", p.to_string_lossy(), s)
+            }
+            Error::InvalidConfig(p, s) => {
+                format!("Invalid configuration in {}: {}", p.to_string_lossy(), s)
+            }
             Error::Other(s) => s.to_string(),
         }
     }
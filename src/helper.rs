@@ -1,3 +1,4 @@
+use crate::text::truncate_html;
 use chrono::DateTime;
 use handlebars::{
     Context, Handlebars, Helper, HelperDef, RenderContext, RenderError, RenderErrorReason,
@@ -119,3 +120,57 @@ impl HelperDef for JoinHelper {
         )))
     }
 }
+
+/// A Handlebars helper that shortens HTML to a number of words.
+///
+/// The first parameter is the property to be truncated. It must be a String
+/// that contains HTML. Any elements left open by the truncation are closed.
+/// The second parameter is optional, and specifies the maximum number of words.
+/// If no length is specified, 50 is used as a default.
+///
+/// # Examples
+/// ```no_compile
+/// // abstract: "<p>It was in the spring of the year 1894</p>"
+///
+/// {{{truncate abstract 5}}}
+///
+/// // output: "<p>It was in the spring…</p>"
+/// ```
+pub(crate) struct TruncateHelper {}
+
+impl TruncateHelper {
+    /// Create a new TruncateHelper.
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl HelperDef for TruncateHelper {
+    fn call_inner<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        _: &'reg Handlebars<'reg>,
+        _: &'rc Context,
+        _: &mut RenderContext<'reg, 'rc>,
+    ) -> std::result::Result<ScopedJson<'rc>, RenderError> {
+        let value = h
+            .param(0)
+            .map(|pj| pj.value())
+            .filter(|v| !v.is_null())
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                RenderErrorReason::Other("Property cannot be converted to string.".into())
+            })?;
+
+        let words = h
+            .param(1)
+            .map(|p| p.value())
+            .filter(|v| !v.is_null())
+            .and_then(|v| v.as_u64())
+            .unwrap_or(50);
+
+        Ok(ScopedJson::Derived(serde_json::value::Value::String(
+            truncate_html(value, words as usize),
+        )))
+    }
+}
@@ -0,0 +1,142 @@
+//! Build-time syntax highlighting of fenced code blocks.
+//!
+//! Code is highlighted with [syntect], which marks up each token with CSS
+//! classes rather than inline styles. The colors come from a stylesheet that
+//! can be generated from any of the [syntect] themes with [stylesheet].
+
+use crate::{error::Error, Result};
+use pulldown_cmark::{CodeBlockKind, CowStr, Event, Tag, TagEnd};
+use std::sync::OnceLock;
+use syntect::{
+    highlighting::ThemeSet,
+    html::{css_for_theme_with_class_style, ClassStyle, ClassedHTMLGenerator},
+    parsing::SyntaxSet,
+    util::LinesWithEndings,
+};
+
+/// The name of the stylesheet generated in the destination directory.
+pub const STYLESHEET_FILE: &str = "highlight.css";
+
+/// The prefix of the CSS classes of highlighted tokens.
+const CLASS_STYLE: ClassStyle = ClassStyle::SpacedPrefixed { prefix: "hl-" };
+
+/// Get the syntax definitions, which are loaded the first time they are used.
+fn syntax_set() -> &'static SyntaxSet {
+    static SYNTAX_SET: OnceLock<SyntaxSet> = OnceLock::new();
+    SYNTAX_SET.get_or_init(SyntaxSet::load_defaults_newlines)
+}
+
+/// Get the names of the themes that can be used with [stylesheet].
+///
+/// # Example
+///
+/// ```
+/// use bloggo::highlight::theme_names;
+///
+/// assert!(theme_names().contains(&"InspiredGitHub".to_string()));
+/// ```
+pub fn theme_names() -> Vec<String> {
+    ThemeSet::load_defaults().themes.into_keys().collect()
+}
+
+/// Generate the stylesheet that colors highlighted code using the named
+/// theme.
+pub fn stylesheet(theme_name: &str) -> Result<String> {
+    let themes = ThemeSet::load_defaults();
+    let theme = themes.themes.get(theme_name).ok_or_else(|| {
+        Error::Other(format!(
+            "Unknown highlight theme: {} (expected one of {})",
+            theme_name,
+            theme_names().join(", ")
+        ))
+    })?;
+    css_for_theme_with_class_style(theme, CLASS_STYLE)
+        .map_err(|e| Error::Other(format!("Could not generate stylesheet: {}", e)))
+}
+
+/// Highlight source code in the language with the given name or file
+/// extension, returning [None] if the language is unknown.
+fn highlight(code: &str, language: &str) -> Option<String> {
+    let ss = syntax_set();
+    let syntax = ss.find_syntax_by_token(language)?;
+    let mut generator = ClassedHTMLGenerator::new_with_class_style(syntax, ss, CLASS_STYLE);
+    for line in LinesWithEndings::from(code) {
+        generator
+            .parse_html_for_line_which_includes_newline(line)
+            .ok()?;
+    }
+    Some(generator.finalize())
+}
+
+/// Replace the fenced code blocks in a list of Markdown events whose language
+/// is known with highlighted HTML. Other code blocks are left unchanged.
+pub(crate) fn highlight_code_blocks(events: Vec<Event>) -> Vec<Event> {
+    let mut result = Vec::with_capacity(events.len());
+    let mut code_block: Option<(usize, String)> = None;
+    let mut code = String::new();
+
+    for event in events {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(ref info))) => {
+                let language = info.split_whitespace().next().unwrap_or("");
+                code_block = Some((result.len(), String::from(language)));
+                code.clear();
+                result.push(event);
+            }
+            Event::Text(ref t) if code_block.is_some() => {
+                code.push_str(t);
+                result.push(event);
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                let highlighted = code_block.take().and_then(|(start, language)| {
+                    highlight(&code, &language).map(|html| (start, language, html))
+                });
+                match highlighted {
+                    Some((start, language, html)) => {
+                        result.truncate(start);
+                        result.push(Event::Html(CowStr::from(format!(
+                            "<pre class=\"highlight\"><code class=\"language-{}\">{}</code></pre>\n",
+                            language, html
+                        ))));
+                    }
+                    None => result.push(event),
+                }
+            }
+            _ => result.push(event),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use pulldown_cmark::{html, Parser};
+
+    fn render(source: &str) -> String {
+        let events = highlight_code_blocks(Parser::new(source).collect());
+        let mut text = String::new();
+        html::push_html(&mut text, events.into_iter());
+        text
+    }
+
+    #[test]
+    fn highlight_code_blocks_known_language() {
+        let text = render("```rust\nfn main() {}\n```\n");
+        assert!(text.starts_with("<pre class=\"highlight\"><code class=\"language-rust\">"));
+        assert!(text.contains("<span class=\"hl-storage hl-type hl-function hl-rust\">fn</span>"));
+    }
+
+    #[test]
+    fn highlight_code_blocks_unknown_language() {
+        assert_eq!(
+            "<pre><code class=\"language-nonsense\">a &lt; b\n</code></pre>\n",
+            render("```nonsense\na < b\n```\n")
+        );
+    }
+
+    #[test]
+    fn stylesheet_unknown_theme() {
+        assert!(stylesheet("No Such Theme").is_err());
+    }
+}
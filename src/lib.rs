@@ -14,9 +14,10 @@
 //! Usage: bloggo [OPTIONS] <COMMAND>
 //!
 //! Commands:
-//!   clean  Clean destination directory
-//!   build  Build static site pages
-//!   help   Print this message or the help of the given subcommand(s)
+//!   clean        Clean destination directory
+//!   build        Build static site pages
+//!   check-links  Check the links in the built site
+//!   help         Print this message or the help of the given subcommand(s)
 //!
 //! Options:
 //!   -s, --source <DIR>  Directory containing post and template source [default: source/]
@@ -38,9 +39,17 @@
 //! |-- templates
 //! |   |-- index.html.hbs
 //! |   |-- post.html.hbs
+//! |   |-- shortcodes
+//! |   |   |-- figure.html.hbs
 //! |-- posts
-//!     |-- 2023-03-01_an_example_post.md
-//!     |-- 2023-03-02_another_post.md
+//! |   |-- 2023-03-01_an_example_post.md
+//! |   |-- 2023-03-02_another_post.md
+//! |   |-- 2023-03-03_a_bundle
+//! |   |   |-- index.md
+//! |   |   |-- photo.jpg
+//! |-- pages
+//! |   |-- about.md
+//! |-- bloggo.toml
 //! ```
 //!
 //! The `assets` directory is copied as-is to the destination directory. A file
@@ -51,27 +60,83 @@
 //! phase of Bloggo will parse each post, and render an HTML file in the
 //! destination using a template. The source file is similar to other static site
 //! generators: it includes a "front matter" block, delimited to describe the
-//! format of the front matter (three hyphens for YAML), followed by the Markdown
-//! content. The front matter properties are passed into the template renderer,
-//! and some of the properties are special:
+//! format of the front matter (three hyphens for YAML, three plus signs for
+//! TOML, or a JSON object starting with an opening brace), followed by the
+//! Markdown content. The front matter block is optional; a file that does not
+//! start with one, such as a file that starts with a shortcode (`{{<`), is
+//! treated as Markdown content only. The front matter
+//! properties are passed into the template renderer, and some of the properties
+//! are special:
 //!
 //! - `date`: If present, this is used as the `date` property in the Handlebars
 //!   template. The expected format is the date and time format specified by
-//!   [ISO 8601](https://en.wikipedia.org/wiki/ISO_8601#Combined_date_and_time_representations).
+//!   [ISO 8601](https://en.wikipedia.org/wiki/ISO_8601#Combined_date_and_time_representations)
+//!   or [RFC 2822](https://www.rfc-editor.org/rfc/rfc2822#section-3.3). A date
+//!   without a time (`2023-04-05`) or a date and time without an offset
+//!   (`2023-04-05 14:00`) is interpreted in the site time zone.
 //!   If not present, Bloggo will attempt to guess based on the name
 //!   of the file: a file named `2023-04-05_example.md` will have a `date` property
-//!   of `2023-04-05T00:00:00Z`.
+//!   of midnight on April 5, 2023 in the site time zone. For the index of a
+//!   bundle, the name of the bundle directory is used instead. A `date` that
+//!   cannot be parsed is reported as an error, naming the file of the post.
+//! - `updated`: If present, this is the date and time the post was last
+//!   updated, in the same format as `date`.
+//! - `title`: If the file has no front matter, Bloggo will use the text of the
+//!   first level one heading in the Markdown source as the title, and remove
+//!   that heading from the rendered text.
 //! - `layout`: The name of the template used to render this post. The name of the
 //!   layout must refer to a file that appears in the `templates` directory with
-//!   the filename extension of `.html.hbs`. The default value is `post`.
+//!   the filename extension of `.html.hbs`. The default value is `post`, or
+//!   `default` for sites that have a `default.html.hbs` template but no
+//!   `post.html.hbs` template.
 //! - `tags`: An array of strings. Bloggo generates an index and feed for each
-//!   tag in the site.
+//!   tag in the site, in a directory named by the slug of the tag, such as
+//!   `rust-lang` for `Rust Lang`. Letters outside of ASCII are kept in the slug
+//!   and percent-encoded in the url of the index. Two tags with the same slug
+//!   are an error, as is a tag whose slug is the name of a directory used by
+//!   the site, such as `series`, `tags` or the output directory of a
+//!   collection.
+//! - `series`: The name of a series of posts that this post is part of, such as
+//!   a multi-part article. Bloggo generates an index for each series, in a
+//!   directory named by the slug of the series, like the indexes of tags. Two
+//!   series with the same slug are an error.
+//! - `series_order`: The position of this post in its series. Parts without a
+//!   `series_order` follow the others, and parts are otherwise ordered by date.
+//! - `draft`: If `true`, the post is left out of the site, including the indexes
+//!   and feeds. Drafts are included when the `--drafts` option is passed to the
+//!   `build` command.
+//! - `publish_date`: If present, the post is left out of the site until this
+//!   date, rather than the `date` of the post. Posts with a publish date in the
+//!   future are included when the `--future` option is passed to the `build`
+//!   command.
+//! - `expiry_date`: If present, the post is left out of the site, including the
+//!   indexes and feeds, after this date.
+//! - `markdown`: A map that enables or disables Markdown extensions for this post,
+//!   overriding the `markdown` setting of the site configuration. For example,
+//!   `markdown: { smart_punctuation: true }` turns straight quotes into curly
+//!   quotes.
 //! - `abstract`: If not present, Bloggo will populate this property with the
-//!   first paragraph that appears in the Markdown source.
+//!   content that appears before the excerpt separator (`<!--more-->`) in the
+//!   Markdown source, or with the first paragraph if there is no separator.
+//! - `summary`: If not present, Bloggo will populate this property with the
+//!   plain text of the `abstract`, without any HTML tags, shortened to a number
+//!   of words. This is suitable for `<meta name="description">` elements.
+//! - `word_count`: The number of words in the text of the post. This property is
+//!   generated by Bloggo.
+//! - `reading_time`: The estimated number of minutes needed to read the post,
+//!   based on its `word_count`. This property is generated by Bloggo.
 //! - `path`: The path of the generated HTML file in the build directory. This
 //!   property is generated by Bloggo.
 //! - `url`: This is the URL of the post, formed by concatenating the base url
 //!   with the `path`. This property is generated by Bloggo.
+//! - `toc`: The table of contents of the post, generated by Bloggo. Every heading
+//!   in the Markdown source is given an `id` attribute derived from its text, so
+//!   it can be linked to. Each entry in the `toc` array has the properties:
+//!   - `level`: the level of the heading, from 1 to 6.
+//!   - `text`: the text of the heading.
+//!   - `id`: the `id` attribute of the heading.
+//!   - `children`: an array of entries for the headings of a lower level that
+//!     follow the heading.
 //!
 //! The `templates` directory contains the Handlebars templates used to render
 //! posts. The following two template files are required; additional template
@@ -82,19 +147,82 @@
 //! - `index.html.hbs`: Used to render the site and tag indexes.
 //! - `post.html.hbs`: The default template for individual post pages.
 //!
+//! The optional `page.html.hbs` template is the default template for pages,
+//! which are rendered with `post.html.hbs` if it does not exist. The optional
+//! `series.html.hbs` template is used to render the index of each series, which
+//! is rendered with `index.html.hbs` if it does not exist. The optional
+//! `tags.html.hbs` template is used to render the index of all of the tags to
+//! `tags/index.html`, which is rendered with `index.html.hbs` if it does not
+//! exist.
+//!
 //! The object passed into the Handlebars renderer includes the properties:
 //! - `posts`: An array of Post objects to render. In the site index, this contains
 //!   all of the posts in the sites. In each tag index, this contains the posts
 //!   that have the tag. In individual posts, this array will contain the single
 //!   post to render. Each post object contains the properties specified in the
 //!   front matter.
-//! - `tags`: An array of Tag objects used in the site. Each Tag object has the
+//! - `collection`: The name of the collection of the posts in the `posts` array,
+//!   present when the index of a collection, a tag index, or a post is being
+//!   rendered.
+//! - `post`: The post being rendered, present when an individual post is being
+//!   rendered.
+//! - `prev`: The previous post in the collection of the post being rendered, if
+//!   there is one. In a collection sorted by date, this is the next older post.
+//! - `next`: The next post in the collection of the post being rendered, if
+//!   there is one. In a collection sorted by date, this is the next newer post.
+//! - `tag_navigation`: An object containing, for each tag of the post being
+//!   rendered, the `prev` and `next` posts with that tag by date, such as
+//!   `tag_navigation.rust.prev`.
+//! - `related`: An array of the posts that share the most tags with the post
+//!   being rendered, newest first among posts that share the same number of
+//!   tags.
+//! - `site`: The whole site, which is available to every template. It has the
 //!   properties:
+//!   - `posts`: an array of all of the posts in the site, newest first.
+//!   - `pages`: an array of the pages in the site, which can be used to build a
+//!     navigation menu.
+//!   - `collections`: an object containing an array of the posts of each
+//!     collection other than `posts` and `pages`, by name, such as
+//!     `site.collections.talks`.
+//!   - `taxonomies`: an object containing an array of the terms of each
+//!     taxonomy other than `tags`, by name, such as `site.taxonomies.categories`.
+//!     Each term is an object with the same properties as a Tag object.
+//! - `tags`: An array of Tag objects used in the site, sorted by name. Each Tag
+//!   object has the properties:
 //!   - `name`: the name of the tag.
+//!   - `slug`: the name of the tag in the path and url of its index.
 //!   - `count`: the number of posts with the tag.
+//!   - `weight`: the count of the tag on a scale from 1, for the least used
+//!     tags, to 10, for the most used, which can be used to render a tag cloud.
+//!   - `recent_posts`: an array of the newest posts with the tag.
 //!   - `index_url`: The url of the index page for the tag.
 //! - `tag`: The tag of the posts in the `posts` array, present when the tag index
-//!   is being rendered.
+//!   is being rendered. When the index of a term of another taxonomy is being
+//!   rendered, this is the term.
+//! - `taxonomy`: The name of the taxonomy of `tag`, present when the index of a
+//!   tag or other term, or the index of all of the terms of a taxonomy, is
+//!   being rendered.
+//! - `terms`: An array of the terms of `taxonomy`, such as the Tag objects of
+//!   the `tags` taxonomy, present whenever `taxonomy` is.
+//! - `series`: The series of the post being rendered, if it is part of one, or
+//!   the series of the posts in the `posts` array when the index of a series is
+//!   being rendered. The series object has the properties:
+//!   - `name`: the name of the series.
+//!   - `index_url`: the url of the index page for the series.
+//!   - `parts`: an array of the posts in the series, in order.
+//!   - `count`: the number of posts in the series.
+//!   - `position`: the position of the post being rendered in the series,
+//!     starting from 1.
+//!   - `prev`: the previous post in the series, if there is one.
+//!   - `next`: the next post in the series, if there is one.
+//! - `word_count`: The total `word_count` of the posts in the `posts` array.
+//! - `reading_time`: The total `reading_time` of the posts in the `posts` array.
+//!
+//! The posts in `prev`, `next`, `tag_navigation`, `related` and `site`, in the
+//! `recent_posts` of a Tag object, and in the `parts`, `prev` and `next` of a
+//! series, only have the `title`, `url` and `date` properties of each post, so
+//! that the text of every post is not repeated in every page. Only the posts in
+//! the `posts` array and `post` have all of their properties.
 //!
 //! When the `bloggo build` command is used with the source directory described
 //! above, the destination directory will contain
@@ -105,13 +233,23 @@
 //! |-- atom.xml
 //! |-- 2023-03-01_an_example_post.html
 //! |-- 2023-03-02_another_post.html
+//! |-- 2023-03-03_a_bundle
+//! |   |-- index.html
+//! |   |-- photo.jpg
+//! |-- about.html
 //! |-- tag-1
 //! |   |-- index.html
 //! |   |-- atom.xml
 //! |-- tag-2
 //! |   |-- index.html
 //! |   |-- atom.xml
+//! |-- tags
+//! |   |-- index.html
+//! |-- series
+//! |   |-- a-series
+//! |   |   |-- index.html
 //! |-- style.css
+//! |-- highlight.css
 //! |-- images
 //!     |-- example.jpg
 //!     |-- example.png
@@ -119,11 +257,184 @@
 //!
 //! This directory can be copied to a web server that can serve static files.
 //!
+//! The Markdown (`.md`) and HTML (`.html`) files in the `posts` directory are
+//! posts. Other files, such as images, are copied to the same place in the
+//! destination directory. A post and the files it uses can be kept together in a
+//! post bundle: a directory containing an `index.md` or `index.html` file, which
+//! is the post, along with any other files, which are copied next to the
+//! rendered post. The `url` of a bundled post is the URL of its directory, and
+//! the relative links and images in it are made absolute, so that they also work
+//! when the post is included in an index. The files of a bundle are only copied
+//! if its post is published.
+//!
+//! Only `.md` and `.html` files are posts. Earlier versions of Bloggo parsed
+//! every file in the `posts` directory as a post, so a warning, shown with the
+//! `--verbose` option, is logged when another text file, such as a `.markdown`
+//! or `.txt` file, is copied as a resource instead.
+//!
+//! The `pages` directory contains standalone pages, such as an About or Contact
+//! page, which are parsed in the same way as posts but are not included in the
+//! site index, the tag indexes or the feeds. The pages are sorted by their
+//! optional `weight` property, and then by their title.
+//!
+//! Posts can embed the output of templates in the `templates/shortcodes`
+//! directory with shortcodes. The shortcode
+//! `{{< figure src="holmes.jpg" caption="Sherlock Holmes" >}}` is replaced by
+//! the output of the `templates/shortcodes/figure.html.hbs` template, which is
+//! passed the properties `src` and `caption`. A shortcode can also enclose
+//! content, which is passed to the template as the `inner` property:
+//!
+//! ```text
+//! {{< callout kind="warning" >}}
+//! The *game* is afoot.
+//! {{< /callout >}}
+//! ```
+//!
+//! Shortcodes are expanded before the Markdown is converted to HTML, so a
+//! template that surrounds `{{{inner}}}` with blank lines lets the enclosed
+//! content be formatted as Markdown. To write a shortcode literally, enclose it
+//! in comments: `{{</* figure */>}}`.
+//!
+//! Posts can link to each other by the names of their source files, as in
+//! `[the other case](2023-03-01_other.md)`, so that the links also work when the
+//! Markdown is viewed in an editor or on GitHub. Relative links to Markdown
+//! files are resolved from the directory of the linking post and replaced with
+//! the `url` of the target post, keeping any `?query` and `#fragment`. Escaped
+//! characters such as `%20` are decoded before the file is looked up. The build
+//! fails if a link refers to a file that does not exist, or to a post that is
+//! not published, such as a draft.
+//!
+//! The `bloggo check-links` command checks every `href` and `src` attribute in
+//! the HTML files of the built site. Links to pages and assets within the site,
+//! including absolute links that begin with the base URL, must refer to a file
+//! in the destination directory, or to a directory containing an `index.html`
+//! file. Escaped characters such as `%20` are decoded before the file is looked
+//! up. Each broken link is reported with the source post and line that contains
+//! it, as it was written in the source, and the command fails if any are found.
+//! Passing the `--check-links` option to the `build` command checks the links
+//! at the end of the build.
+//!
+//! Fenced code blocks in the Markdown source whose language is known, such as
+//! ```` ```rust ````, are highlighted when the site is built. Each token is
+//! marked up with a CSS class beginning with `hl-`, and the colors are defined
+//! in the `highlight.css` stylesheet, which Bloggo generates in the destination
+//! directory from the `highlight_theme` setting. A `highlight.css` file in the
+//! `assets` directory is copied over the generated one, so a site can provide
+//! its own colors.
+//!
+//! # Configuration
+//!
+//! Site-wide settings are read from the optional `bloggo.toml` file in the
+//! source directory. The following settings are supported:
+//!
+//! - `timezone`: The name of the time zone from the
+//!   [IANA time zone database](https://www.iana.org/time-zones) used for dates
+//!   without an offset, such as `America/New_York`. The default is `UTC`.
+//! - `excerpt_separator`: The marker that separates the abstract of a post from
+//!   the rest of its text. The default is `<!--more-->`.
+//! - `summary_words`: The maximum number of words in the `summary` of a post. The
+//!   default is `50`.
+//! - `words_per_minute`: The reading speed used to calculate the `reading_time`
+//!   of a post. The default is `200`.
+//! - `related_posts`: The maximum number of posts in the `related` list of each
+//!   post. The default is `5`.
+//! - `recent_posts`: The maximum number of posts in the `recent_posts` list of
+//!   each tag. The default is `5`.
+//! - `highlight_theme`: The theme used to color fenced code blocks. The default
+//!   is `InspiredGitHub`; the other themes are `Solarized (dark)`,
+//!   `Solarized (light)`, `base16-eighties.dark`, `base16-mocha.dark`,
+//!   `base16-ocean.dark` and `base16-ocean.light`.
+//! - `markdown`: A table that enables or disables Markdown extensions for every
+//!   post, such as `markdown = { smart_punctuation = true }`. The extensions are
+//!   `tables`, `footnotes`, `strikethrough`, `tasklists`, `heading_attributes`
+//!   and `smart_punctuation`. All of them are enabled by default, except
+//!   `smart_punctuation`.
+//! - `collections`: A table of collections of posts, described below.
+//! - `taxonomies`: A table of taxonomies of posts, described below.
+//!
+//! Besides the `posts` and `pages` directories, a site can have other
+//! collections of posts, such as talks or projects, each with its own source
+//! directory. A collection is declared with a table in `bloggo.toml`:
+//!
+//! ```toml
+//! [collections.talks]
+//! layout = "talk"
+//! sort = "title"
+//! feed = true
+//! ```
+//!
+//! Each collection supports the following settings:
+//!
+//! - `source_dir`: The directory in the source directory that contains the
+//!   posts. The default is the name of the collection.
+//! - `output_dir`: The directory in the destination directory to which the posts
+//!   are rendered. The default is the name of the collection. Collections can
+//!   share an output directory as long as only one of them has an index and
+//!   only one has a feed. It cannot be `series`, or the output directory of a
+//!   taxonomy.
+//! - `layout`: The template used to render each post, unless a post has a
+//!   `layout` property. If the template does not exist, `post.html.hbs` is
+//!   used. The default is `post`.
+//! - `sort`: The order of the posts: `date` for newest first, `title`, or
+//!   `weight` for the `weight` property and then the title. The default is
+//!   `date`.
+//! - `index`: Whether an index of the collection is rendered to `index.html` in
+//!   the output directory, using `index.html.hbs`. The default is `true`.
+//! - `feed`: Whether an Atom feed of the collection is rendered to `atom.xml` in
+//!   the output directory. The default is `false`.
+//!
+//! The `posts` and `pages` directories are the built-in collections `posts` and
+//! `pages`, which are rendered to the top of the destination directory. Their
+//! settings can be changed in the same way, except that only the posts in the
+//! `posts` collection are included in the tag indexes.
+//!
+//! The build fails if two files of the site would be rendered to the same path,
+//! such as a page and a post with the same name, a page named `index.md` in the
+//! same directory as the index of the posts, or a tag with the same name as the
+//! output directory of a collection.
+//!
+//! Besides tags, the posts can be classified by other taxonomies, such as
+//! categories or authors. A taxonomy is declared with a table in `bloggo.toml`:
+//!
+//! ```toml
+//! [taxonomies.categories]
+//! key = "category"
+//! template = "category"
+//! feed = true
+//! ```
+//!
+//! Each taxonomy supports the following settings:
+//!
+//! - `key`: The front matter property that contains the terms of each post, as
+//!   a string or an array of strings. The default is the name of the taxonomy.
+//! - `output_dir`: The directory in the destination directory to which the
+//!   index of each term is rendered. The default is the name of the taxonomy.
+//!   It must differ from the output directories of the other taxonomies, and
+//!   cannot be `series`.
+//! - `template`: The template used to render the index of each term. If the
+//!   template does not exist, `index.html.hbs` is used. The default is `index`.
+//! - `feed`: Whether an Atom feed of each term is rendered next to its index.
+//!   The default is `false`.
+//! - `overview`: Whether an index of all of the terms is rendered to
+//!   `index.html` in the output directory, using the template named after the
+//!   taxonomy, such as `categories.html.hbs`, or `index.html.hbs` if it does
+//!   not exist. If the output directory is the top of the destination
+//!   directory, it is rendered to a directory named after the taxonomy
+//!   instead. The `template` setting must then differ from the name of the
+//!   taxonomy. The default is `false`.
+//!
+//! The `tags` property is the built-in taxonomy `tags`, whose indexes and feeds
+//! are rendered to the top of the destination directory, with the index of all
+//! of the tags in `tags/index.html`. Its settings can be changed in the same
+//! way, and `overview = false` turns off the index of all of the tags. Only the
+//! posts in the `posts` collection are classified by taxonomies.
+//!
 //! # Acknowledgements
 //!
 //! Bloggo depends on a number of open source projects.
 //!
 //! - [chrono](https://crates.io/crates/chrono)
+//! - [chrono-tz](https://crates.io/crates/chrono-tz)
 //! - [clap](https://crates.io/crates/clap)
 //! - [env_logger](https://crates.io/crates/env_logger)
 //! - [handlebars](https://crates.io/crates/handlebars)
@@ -132,30 +443,42 @@
 //! - [serde](https://crates.io/crates/serde)
 //! - [serde_json](https://crates.io/crates/serde_json)
 //! - [serde_yaml](https://crates.io/crates/serde_yaml)
+//! - [syntect](https://crates.io/crates/syntect)
 //! - [toml](https://crates.io/crates/toml)
 //!
 //! # License
 //!
 //! Bloggo is distributed under the terms of the MIT License.
 
+pub mod config;
 pub mod error;
 pub mod fs;
 pub mod helper;
+pub mod highlight;
+pub mod links;
+mod markdown;
+mod shortcode;
+pub mod text;
 pub mod value;
 
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use config::{Collection, Config, SortOrder, Taxonomy};
 use error::Error;
 use handlebars::{DirectorySourceOptions, Handlebars};
-use helper::{FormatDateTimeHelper, JoinHelper};
-use log::{debug, info};
-use pulldown_cmark::{html, Options, Parser};
+use helper::{FormatDateTimeHelper, JoinHelper, TruncateHelper};
+use links::BrokenLink;
+use log::{debug, error, info, warn};
+use markdown::{render_markdown, Markdown};
 use serde::{ser::SerializeMap, Serialize, Serializer};
 use std::{
     borrow::Borrow,
-    collections::HashMap,
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
     fs::File,
     io::{BufRead, BufReader, BufWriter, Read, Write},
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
 };
 use value::Value;
 
@@ -187,6 +510,11 @@ pub struct Bloggo<'a> {
     src_dir: String,
     dest_dir: String,
     base_url: String,
+    config: Config,
+    include_drafts: bool,
+    include_future: bool,
+    link_check: bool,
+    now: DateTime<Utc>,
     handlebars: Handlebars<'a>,
 }
 
@@ -197,10 +525,16 @@ impl<'a> Bloggo<'a> {
         let mut handlebars = Handlebars::new();
         handlebars.register_helper("formatDateTime", Box::new(FormatDateTimeHelper::new()));
         handlebars.register_helper("join", Box::new(JoinHelper::new()));
+        handlebars.register_helper("truncate", Box::new(TruncateHelper::new()));
         Self {
             src_dir,
             dest_dir,
             base_url,
+            config: Config::default(),
+            include_drafts: false,
+            include_future: false,
+            link_check: false,
+            now: SystemTime::now().into(),
             handlebars,
         }
     }
@@ -211,9 +545,16 @@ impl<'a> Bloggo<'a> {
         fs::remove_dir_all(&self.dest_dir).map_err(|e| e.into())
     }
 
-    /// Builds the static site by copying assets and generating HTML.
-    pub fn build(&mut self) -> Result<()> {
-        info!("Building from {} to {}", self.src_dir, self.dest_dir);
+    /// Read the site configuration and register the templates from the
+    /// source directory.
+    fn load(&mut self) -> Result<()> {
+        let mut config_path = PathBuf::new();
+        config_path.push(&self.src_dir);
+        config_path.push(config::CONFIG_FILE);
+        if config_path.exists() {
+            info!("Reading configuration from {}", config_path.display());
+            self.config = Config::from_file(&config_path)?;
+        }
 
         let mut template_dir = PathBuf::new();
         template_dir.push(&self.src_dir);
@@ -236,47 +577,272 @@ impl<'a> Bloggo<'a> {
         };
         self.handlebars
             .register_templates_directory(&template_dir, xml_options)?;
+        Ok(())
+    }
+
+    /// Builds the static site by copying assets and generating HTML.
+    pub fn build(&mut self) -> Result<()> {
+        info!("Building from {} to {}", self.src_dir, self.dest_dir);
+        self.load()?;
 
         fs::create_dir_all(&self.dest_dir)?;
+        self.write_highlight_stylesheet()?;
         self.copy_assets()?;
-        let all_posts = self.parse_posts()?;
+        let mut collections = Vec::with_capacity(self.config.collections.len());
+        let mut post_links = Vec::new();
+        for collection in &self.config.collections {
+            let (posts, links) = self.parse_collection(collection)?;
+            self.copy_post_resources(collection, &posts)?;
+            collections.push(CollectionPosts { collection, posts });
+            post_links.extend(links);
+        }
+        self.check_post_links(&post_links)?;
+        let no_posts = Vec::new();
+        let all_posts = collections
+            .iter()
+            .find(|c| c.collection.name == "posts")
+            .map_or(&no_posts, |c| &c.posts);
 
-        // Generate tag indices.
-        let tag_index = self.generate_tag_indexes(&all_posts);
-        let tags: Vec<Tag> = tag_index
+        // Generate the term indices of each taxonomy.
+        let term_indexes: Vec<HashMap<String, Vec<&Post>>> = self
+            .config
+            .taxonomies
             .iter()
-            .map(|entry| Tag {
-                name: entry.0,
-                index_url: format!("{}/{}/", self.base_url, entry.0),
-                count: entry.1.len(),
-            })
+            .map(|t| self.generate_term_indexes(all_posts, &t.key))
             .collect();
-
-        let all_posts_refs: Vec<&Post> = all_posts.iter().collect();
-        let mut render_context = RenderContext {
-            tag: None,
-            tags: &tags,
-            posts: &all_posts_refs,
+        let taxonomies: Vec<TaxonomyTerms> = self
+            .config
+            .taxonomies
+            .iter()
+            .zip(&term_indexes)
+            .map(|(taxonomy, term_index)| self.generate_terms(taxonomy, term_index))
+            .collect::<Result<Vec<TaxonomyTerms>>>()?;
+
+        let series = self.generate_series(all_posts)?;
+        self.check_output_paths(&collections, &taxonomies, &series)?;
+        let site = Site {
+            posts: all_posts,
+            taxonomies: &taxonomies,
+            collections: &collections,
+            series: &series,
         };
 
-        self.render_index(&render_context, &PathBuf::from("index.html"))?;
-        self.render_atom_feed(&render_context, &PathBuf::from("atom.xml"))?;
-        for (tag, posts) in &tag_index {
-            let mut index_path = PathBuf::from(tag);
-            index_path.push("index.html");
-            render_context.tag = Some(tag);
-            render_context.posts = posts;
-            self.render_index(&render_context, &index_path)?;
+        for c in &collections {
+            let posts: Vec<&Post> = c.posts.iter().collect();
+            let render_context = RenderContext {
+                collection: Some(&c.collection.name),
+                taxonomy: None,
+                tag: None,
+                series: None,
+                post: None,
+                posts: &posts,
+                site,
+            };
+            let output_dir = PathBuf::from(&c.collection.output_dir);
+            if c.collection.index {
+                self.render_index("index", &render_context, &output_dir.join("index.html"))?;
+            }
+            if c.collection.feed {
+                self.render_atom_feed(&render_context, &output_dir.join("atom.xml"))?;
+            }
+        }
+
+        for t in &taxonomies {
+            if let Some(overview_dir) = t.taxonomy.overview_dir() {
+                let overview_template = if self.handlebars.has_template(&t.taxonomy.name) {
+                    &t.taxonomy.name
+                } else {
+                    "index"
+                };
+                let render_context = RenderContext {
+                    collection: Some("posts"),
+                    taxonomy: Some(&t.taxonomy.name),
+                    tag: None,
+                    series: None,
+                    post: None,
+                    posts: &all_posts.iter().collect(),
+                    site,
+                };
+                let overview_path = PathBuf::from(overview_dir).join("index.html");
+                self.render_index(overview_template, &render_context, &overview_path)?;
+            }
 
-            let mut feed_path = PathBuf::from(tag);
-            feed_path.push("atom.xml");
-            self.render_atom_feed(&render_context, &feed_path)?;
+            let template = if self.handlebars.has_template(&t.taxonomy.template) {
+                &t.taxonomy.template
+            } else {
+                "index"
+            };
+            for term in &t.terms {
+                let render_context = RenderContext {
+                    collection: Some("posts"),
+                    taxonomy: Some(&t.taxonomy.name),
+                    tag: Some(term.name),
+                    series: None,
+                    post: None,
+                    posts: term.posts,
+                    site,
+                };
+                let output_dir = PathBuf::from(&t.taxonomy.output_dir).join(&term.slug);
+                self.render_index(template, &render_context, &output_dir.join("index.html"))?;
+                if t.taxonomy.feed {
+                    self.render_atom_feed(&render_context, &output_dir.join("atom.xml"))?;
+                }
+            }
+        }
+        let series_template = if self.handlebars.has_template("series") {
+            "series"
+        } else {
+            "index"
+        };
+        for s in &series {
+            let render_context = RenderContext {
+                collection: Some("posts"),
+                taxonomy: None,
+                tag: None,
+                series: Some(SeriesContext {
+                    series: s,
+                    position: None,
+                }),
+                post: None,
+                posts: &s.parts,
+                site,
+            };
+            let mut index_path = PathBuf::from("series");
+            index_path.push(&s.slug);
+            index_path.push("index.html");
+            self.render_index(series_template, &render_context, &index_path)?;
+        }
+        for c in &collections {
+            self.render_collection(c, &site)?;
+        }
+        if self.link_check {
+            self.report_broken_links(&post_links)?;
         }
-        self.render_posts(&all_posts, &tags)?;
         Ok(())
     }
 
-    fn render_index(&self, render_context: &RenderContext, path: &Path) -> Result<()> {
+    /// Check the links in the HTML files in the destination directory,
+    /// logging each broken link and failing if any were found. The posts are
+    /// parsed again, so that broken links can be reported at their lines in
+    /// the source of each post.
+    pub fn check_links(&mut self) -> Result<()> {
+        self.load()?;
+        let mut post_links = Vec::new();
+        for collection in &self.config.collections {
+            post_links.extend(self.parse_collection(collection)?.1);
+        }
+        self.report_broken_links(&post_links)
+    }
+
+    /// Log each broken link in the HTML files in the destination directory,
+    /// failing if any were found.
+    fn report_broken_links(&self, post_links: &[PostLinks]) -> Result<()> {
+        info!("Checking links in {}", self.dest_dir);
+        let broken_links = self.find_broken_links(post_links)?;
+        for broken_link in &broken_links {
+            error!("{}", broken_link);
+        }
+        if broken_links.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::BrokenLinks(broken_links.len()))
+        }
+    }
+
+    /// Find the links in the HTML files in the destination directory that
+    /// refer to files within the site that do not exist.
+    fn find_broken_links(&self, post_links: &[PostLinks]) -> Result<Vec<BrokenLink>> {
+        let dest_dir = PathBuf::from(&self.dest_dir);
+        let mut broken_links = Vec::new();
+        for rde in fs::recursive_read_dir(&dest_dir)? {
+            let path = rde?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("html") {
+                continue;
+            }
+            let page = path.strip_prefix(&dest_dir)?;
+            let html = std::fs::read_to_string(&path)?;
+            for (line, link) in links::extract_links(&html) {
+                let target = match links::link_target(link, page, &self.base_url) {
+                    Some(target) => dest_dir.join(target),
+                    None => continue,
+                };
+                let exists = if target.is_dir() {
+                    target.join("index.html").is_file()
+                } else {
+                    target.is_file()
+                };
+                if !exists {
+                    debug!("Broken link in {}: {}", path.display(), link);
+                    let broken_link = self
+                        .locate_link_in_post(post_links, page, link)
+                        .or_else(|| self.locate_link_in_source(page, link))
+                        .unwrap_or_else(|| BrokenLink {
+                            file: path.clone(),
+                            line,
+                            link: String::from(link),
+                        });
+                    broken_links.push(broken_link);
+                }
+            }
+        }
+        Ok(broken_links)
+    }
+
+    /// Find the link in the source of a post that was rendered to the given
+    /// link in its generated page, and the first line in the source that
+    /// contains it.
+    fn locate_link_in_post(
+        &self,
+        post_links: &[PostLinks],
+        page: &Path,
+        link: &str,
+    ) -> Option<BrokenLink> {
+        let links = post_links.iter().find(|l| l.page == page)?;
+        let target = links::link_target(link, page, &self.base_url)?;
+        let (source_link, _) = links.rendered.iter().find(|(_, rendered)| {
+            links::link_target(rendered, page, &self.base_url).as_ref() == Some(&target)
+        })?;
+        let text = std::fs::read_to_string(&links.source).ok()?;
+        let line = text
+            .lines()
+            .position(|l| l.contains(source_link.as_str()))?
+            + 1;
+        Some(BrokenLink {
+            file: links.source.clone(),
+            line,
+            link: source_link.clone(),
+        })
+    }
+
+    /// Find the source post or page of a generated page, and the first line
+    /// in it that contains the given link.
+    fn locate_link_in_source(&self, page: &Path, link: &str) -> Option<BrokenLink> {
+        let source = self
+            .config
+            .collections
+            .iter()
+            .filter_map(|c| {
+                let page_dir = page.strip_prefix(&c.output_dir).ok()?.parent()?;
+                std::fs::read_dir(self.collection_dir(c).join(page_dir)).ok()
+            })
+            .flatten()
+            .filter_map(|rde| rde.ok().map(|de| de.path()))
+            .find(|p| p.is_file() && p.file_stem() == page.file_stem())?;
+        let text = std::fs::read_to_string(&source).ok()?;
+        let line = text.lines().position(|l| l.contains(link))? + 1;
+        Some(BrokenLink {
+            file: source,
+            line,
+            link: String::from(link),
+        })
+    }
+
+    fn render_index(
+        &self,
+        template: &str,
+        render_context: &RenderContext,
+        path: &Path,
+    ) -> Result<()> {
         let mut p = PathBuf::new();
         p.push(&self.dest_dir);
         p.push(path);
@@ -285,7 +851,7 @@ impl<'a> Bloggo<'a> {
             fs::create_dir_all(parent)?;
         }
         let mut out = BufWriter::new(File::create(p)?);
-        self.generate_index(render_context, &mut out)?;
+        self.generate_index(template, render_context, &mut out)?;
         out.flush()?;
         Ok(())
     }
@@ -304,16 +870,22 @@ impl<'a> Bloggo<'a> {
         Ok(())
     }
 
+    /// Write the stylesheet for highlighted code to the destination
+    /// directory. A stylesheet of the same name in the assets directory
+    /// replaces it.
+    fn write_highlight_stylesheet(&self) -> Result<()> {
+        let mut p = PathBuf::new();
+        p.push(&self.dest_dir);
+        p.push(highlight::STYLESHEET_FILE);
+        info!("Writing highlight stylesheet to {}", p.display());
+        let css = highlight::stylesheet(&self.config.highlight_theme)?;
+        std::fs::write(p, css)?;
+        Ok(())
+    }
+
     /// Copy all files from the "assets/" source directory to the
     /// destination directory.
     fn copy_assets(&self) -> Result<usize> {
-        fn is_hidden(path: &Path) -> bool {
-            path.file_name()
-                .and_then(|os| os.to_str())
-                .map(|s| s.starts_with('.'))
-                .unwrap_or(false)
-        }
-
         let mut count = 0_usize;
 
         let mut src_dir = PathBuf::new();
@@ -341,32 +913,88 @@ impl<'a> Bloggo<'a> {
         Ok(count)
     }
 
-    /// Render the posts in the source directory to the destination directory.
-    fn render_posts(&self, posts: &Vec<Post>, tags: &Vec<Tag>) -> Result<()> {
-        for post in posts {
-            self.render_post(post, tags)?;
+    /// Render the posts of a collection to the destination directory.
+    /// Each post is linked to the previous and next posts in the collection,
+    /// which are the older and newer posts if it is sorted by date.
+    fn render_collection(&self, c: &CollectionPosts, site: &Site) -> Result<()> {
+        let mut ordered: Vec<&Post> = c.posts.iter().collect();
+        if c.collection.sort == SortOrder::Date {
+            ordered.reverse();
+        }
+        for (i, post) in ordered.iter().enumerate() {
+            let neighbours = Neighbours {
+                prev: i.checked_sub(1).map(|j| ordered[j]),
+                next: ordered.get(i + 1).copied(),
+            };
+            self.render_post(post, c.collection, neighbours, site)?;
         }
 
         Ok(())
     }
 
-    /// Render an individual post to the destination directory.
-    fn render_post(&self, post: &Post, tags: &Vec<Tag>) -> Result<()> {
+    /// Render an individual post to the destination directory, using the
+    /// layout of its collection unless the post specifies its own. If there
+    /// is no template for the layout of the collection, the post template
+    /// is used, or the default template if there is no post template either.
+    fn render_post(
+        &self,
+        post: &Post,
+        collection: &Collection,
+        neighbours: Neighbours,
+        site: &Site,
+    ) -> Result<()> {
+        let default_layout = [collection.layout.as_str(), "post"]
+            .into_iter()
+            .find(|layout| self.handlebars.has_template(layout))
+            .unwrap_or("default");
         let template = post
             .get("layout")
             .and_then(|v| v.as_string())
-            .unwrap_or_else(|| String::from("default"));
+            .unwrap_or_else(|| String::from(default_layout));
         if let Some(Value::String(filename)) = post.get("path") {
             let mut pathbuf = PathBuf::new();
             pathbuf.push(&self.dest_dir);
             pathbuf.push(filename);
             pathbuf.set_extension("html");
+            if let Some(parent) = pathbuf.parent() {
+                fs::create_dir_all(parent)?;
+            }
             let out = File::create(&pathbuf)?;
             info!("Rendering post to {}", pathbuf.display());
+            let series = post
+                .get("series")
+                .and_then(|v| v.as_string())
+                .and_then(|name| site.series.iter().find(|s| s.name == name))
+                .map(|s| SeriesContext {
+                    series: s,
+                    position: s.parts.iter().position(|p| std::ptr::eq(*p, post)),
+                });
+            let tag_navigation = site
+                .tags()
+                .iter()
+                .filter_map(|tag| {
+                    let i = tag.posts.iter().position(|p| std::ptr::eq(*p, post))?;
+                    let neighbours = Neighbours {
+                        prev: tag.posts.get(i + 1).copied(),
+                        next: i.checked_sub(1).map(|j| tag.posts[j]),
+                    };
+                    Some((tag.name, neighbours))
+                })
+                .collect();
+            let related = self.related_posts(post, site.tags());
             let render_context = RenderContext {
+                collection: Some(&collection.name),
+                taxonomy: None,
                 tag: None,
+                series,
+                post: Some(PostContext {
+                    post,
+                    neighbours,
+                    tag_navigation,
+                    related,
+                }),
                 posts: &vec![post],
-                tags,
+                site: *site,
             };
             self.handlebars
                 .render_to_write(&template, &render_context, out)?;
@@ -374,13 +1002,50 @@ impl<'a> Bloggo<'a> {
         Ok(())
     }
 
-    /// Generate an index page using the index template and the list of posts.
-    fn generate_index<W>(&self, render_context: &RenderContext, out: &mut W) -> Result<()>
+    /// Find the posts that share the most tags with a post, up to the limit
+    /// in the configuration. Posts that share the same number of tags are
+    /// ranked newest first.
+    fn related_posts<'b>(&self, post: &Post, tags: &[Tag<'b>]) -> Vec<&'b Post> {
+        // The number of shared tags of each other post, by its address.
+        let mut counts: HashMap<*const Post, (&Post, usize)> = HashMap::new();
+        for tag in tags {
+            if !tag.posts.iter().any(|p| std::ptr::eq(*p, post)) {
+                continue;
+            }
+            for other in tag.posts.iter().filter(|p| !std::ptr::eq(**p, post)) {
+                counts.entry(*other).or_insert((other, 0)).1 += 1;
+            }
+        }
+
+        let mut shared: Vec<(&Post, usize)> = counts.into_values().collect();
+        let date = |p: &Post| p.get("date").and_then(|v| v.as_datetime());
+        let title = |p: &Post| p.get("title").and_then(|v| v.as_string());
+        shared.sort_by(|(a, a_count), (b, b_count)| {
+            b_count
+                .cmp(a_count)
+                .then_with(|| date(b).cmp(&date(a)))
+                .then_with(|| title(a).cmp(&title(b)))
+        });
+        shared
+            .into_iter()
+            .take(self.config.related_posts)
+            .map(|(p, _)| p)
+            .collect()
+    }
+
+    /// Generate an index page using the given template and the list of
+    /// posts.
+    fn generate_index<W>(
+        &self,
+        template: &str,
+        render_context: &RenderContext,
+        out: &mut W,
+    ) -> Result<()>
     where
         W: Write,
     {
         self.handlebars
-            .render_to_write("index", render_context, out)?;
+            .render_to_write(template, render_context, out)?;
         Ok(())
     }
 
@@ -394,7 +1059,102 @@ impl<'a> Bloggo<'a> {
         Ok(())
     }
 
-    fn generate_tag_indexes<'b>(&'b self, posts: &'b Vec<Post>) -> HashMap<String, Vec<&'b Post>> {
+    /// Get the terms of a taxonomy from its index, sorted by name. Each term
+    /// must have a slug that is not empty and that differs from the slugs of
+    /// the other terms, as its index is rendered to a directory named by its
+    /// slug. The weight of each term scales its count from 1, for the least
+    /// used terms, to 10, for the most used.
+    fn generate_terms<'b>(
+        &self,
+        taxonomy: &'b Taxonomy,
+        term_index: &'b HashMap<String, Vec<&'b Post>>,
+    ) -> Result<TaxonomyTerms<'b>> {
+        let mut entries: Vec<(&String, &Vec<&Post>)> = term_index.iter().collect();
+        entries.sort_by_key(|entry| entry.0);
+
+        let reserved = self.reserved_dirs();
+        let mut slugs: HashMap<String, &str> = HashMap::new();
+        let mut terms = Vec::with_capacity(entries.len());
+        for (name, posts) in entries {
+            let slug =
+                insert_slug(&mut slugs, name).map_err(|e| Error::InvalidTag(name.clone(), e))?;
+            if taxonomy.output_dir.is_empty() {
+                if let Some((_, owner)) = reserved.iter().find(|(dir, _)| *dir == slug) {
+                    return Err(Error::InvalidTag(
+                        name.clone(),
+                        format!("has the same slug as the directory of {}", owner),
+                    ));
+                }
+            }
+            let index_url = if taxonomy.output_dir.is_empty() {
+                format!("{}/{}/", self.base_url, links::percent_encode(&slug))
+            } else {
+                format!(
+                    "{}/{}/{}/",
+                    self.base_url,
+                    taxonomy.output_dir,
+                    links::percent_encode(&slug)
+                )
+            };
+            let mut recent_posts = posts.clone();
+            recent_posts.sort_by_key(|p| Reverse(p.get("date").and_then(|v| v.as_datetime())));
+            recent_posts.truncate(self.config.recent_posts);
+            terms.push(Tag {
+                name,
+                slug,
+                index_url,
+                count: posts.len(),
+                weight: 1,
+                posts,
+                recent_posts,
+            });
+        }
+
+        let min = terms.iter().map(|t| t.count).min().unwrap_or(0);
+        let max = terms.iter().map(|t| t.count).max().unwrap_or(0);
+        if max > min {
+            for term in &mut terms {
+                term.weight = 1 + (term.count - min) * 9 / (max - min);
+            }
+        }
+        Ok(TaxonomyTerms { taxonomy, terms })
+    }
+
+    /// Get the directories at the top of the destination directory that are
+    /// used by the collections, the taxonomies and the series, along with
+    /// what uses each of them, which cannot be used as the slug of a term
+    /// that is rendered to the top of the destination directory.
+    fn reserved_dirs(&self) -> Vec<(&str, String)> {
+        fn top_dir(dir: &str) -> &str {
+            dir.split('/').next().unwrap_or(dir)
+        }
+        let mut dirs = vec![("series", String::from("the series"))];
+        for c in &self.config.collections {
+            if !c.output_dir.is_empty() {
+                dirs.push((top_dir(&c.output_dir), format!("the {} collection", c.name)));
+            }
+        }
+        for t in &self.config.taxonomies {
+            if !t.output_dir.is_empty() {
+                dirs.push((top_dir(&t.output_dir), format!("the {} taxonomy", t.name)));
+            }
+            if let Some(overview_dir) = t.overview_dir() {
+                dirs.push((
+                    top_dir(overview_dir),
+                    format!("the index of all {}", t.name),
+                ));
+            }
+        }
+        dirs
+    }
+
+    /// Index the posts by each of the terms in the given property of their
+    /// front matter.
+    fn generate_term_indexes<'b>(
+        &'b self,
+        posts: &'b Vec<Post>,
+        key: &str,
+    ) -> HashMap<String, Vec<&'b Post>> {
         let mut tag_index: HashMap<String, Vec<&Post>> = HashMap::new();
 
         let mut add_post_to_index = |s: &String, p| {
@@ -408,7 +1168,7 @@ impl<'a> Bloggo<'a> {
 
         // generate index structure
         for post in posts {
-            match post.get("tags") {
+            match post.get(key) {
                 Some(Value::String(s)) => {
                     add_post_to_index(s, post);
                 }
@@ -425,32 +1185,321 @@ impl<'a> Bloggo<'a> {
         tag_index
     }
 
-    /// Parse the posts in the source directory.
-    fn parse_posts(&self) -> Result<Vec<Post>> {
-        let mut posts = Vec::new();
-        let mut src_dir = PathBuf::new();
-        src_dir.push(&self.src_dir);
-        src_dir.push("posts");
+    /// Group the posts that are part of a series by the name of the series,
+    /// ordering the parts of each series by their `series_order` property
+    /// and then by date. Like the terms of a taxonomy, each series must have
+    /// a slug that is not empty and that differs from the slugs of the other
+    /// series.
+    fn generate_series<'b>(&self, posts: &'b [Post]) -> Result<Vec<Series<'b>>> {
+        let mut series: Vec<Series> = Vec::new();
+        for post in posts {
+            if let Some(Value::String(name)) = post.get("series") {
+                match series.iter_mut().find(|s| s.name == name) {
+                    Some(s) => s.parts.push(post),
+                    None => series.push(Series {
+                        name,
+                        slug: String::new(),
+                        index_url: String::new(),
+                        parts: vec![post],
+                    }),
+                }
+            }
+        }
+        series.sort_by_key(|s| s.name);
+        let mut slugs: HashMap<String, &str> = HashMap::new();
+        for s in &mut series {
+            s.slug = insert_slug(&mut slugs, s.name)
+                .map_err(|e| Error::InvalidSeries(s.name.to_string(), e))?;
+            s.index_url = format!(
+                "{}/series/{}/",
+                self.base_url,
+                links::percent_encode(&s.slug)
+            );
+        }
+        let order = |p: &Post| {
+            p.get("series_order")
+                .and_then(|v| v.as_f64())
+                .unwrap_or(f64::INFINITY)
+        };
+        let date = |p: &Post| p.get("date").and_then(|v| v.as_datetime());
+        for s in &mut series {
+            s.parts.sort_by(|a, b| {
+                order(a)
+                    .total_cmp(&order(b))
+                    .then_with(|| date(a).cmp(&date(b)))
+            });
+        }
+        Ok(series)
+    }
 
-        for rde in fs::recursive_read_dir(&src_dir)? {
+    /// Parse the published posts of a collection, in the order of the
+    /// collection, along with the links in the source of each of them.
+    fn parse_collection(&self, collection: &Collection) -> Result<(Vec<Post>, Vec<PostLinks>)> {
+        let dir = self.collection_dir(collection);
+        if !dir.is_dir() {
+            info!("Skipping missing collection directory {}", dir.display());
+            return Ok((Vec::new(), Vec::new()));
+        }
+        let mut posts = Vec::new();
+        let mut post_links = Vec::new();
+        for rde in fs::recursive_read_dir(&dir)? {
             let de = rde?;
             let src_path = de.path();
-            posts.push(self.parse_post(src_path)?);
+            if src_path.is_dir() || is_hidden(&src_path) || !self.is_post_source(&src_path) {
+                continue;
+            }
+            let (post, links) = self.parse_post(&src_path)?;
+            if self.is_published(&post) {
+                posts.push(post);
+                post_links.push(links);
+            } else {
+                info!("Skipping unpublished post {}", src_path.display());
+            }
         }
-        posts.sort_by_cached_key(|p| {
-            p.get("date")
-                .and_then(|v| v.as_string())
-                .and_then(|s| DateTime::parse_from_str(&s, "%+").ok())
-                .unwrap_or_else(|| DateTime::parse_from_str("1970-01-01T00:00:00Z", "%+").unwrap())
-            // TODO: Make the Unix Epoch a constant so it doesn't need to be
-            // parsed each time.
+        let title = |p: &Post| p.get("title").and_then(|v| v.as_string());
+        match collection.sort {
+            SortOrder::Date => {
+                posts.sort_by_key(|p| p.get("date").and_then(|v| v.as_datetime()));
+                posts.reverse();
+            }
+            SortOrder::Title => posts.sort_by_key(title),
+            SortOrder::Weight => {
+                let weight = |p: &Post| p.get("weight").and_then(|v| v.as_f64()).unwrap_or(0.0);
+                posts.sort_by(|a, b| {
+                    weight(a)
+                        .total_cmp(&weight(b))
+                        .then_with(|| title(a).cmp(&title(b)))
+                })
+            }
+        }
+        Ok((posts, post_links))
+    }
+
+    /// Check that the posts linked to from the source of each post are
+    /// published, as the links to unpublished posts would be broken.
+    fn check_post_links(&self, post_links: &[PostLinks]) -> Result<()> {
+        let published: HashSet<&Path> = post_links.iter().map(|l| l.source.as_path()).collect();
+        for links in post_links {
+            for (link, target) in &links.posts {
+                if !published.contains(target.as_path()) {
+                    return Err(Error::BrokenLink(
+                        links.source.as_os_str().to_os_string(),
+                        format!("{} is not published", link),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that no two files of the built site are rendered to the same
+    /// path in the destination directory, such as a page and a post with
+    /// the same name in collections with the same output directory, or a
+    /// tag named after a collection.
+    fn check_output_paths(
+        &self,
+        collections: &[CollectionPosts],
+        taxonomies: &[TaxonomyTerms],
+        series: &[Series],
+    ) -> Result<()> {
+        let mut outputs: Vec<(PathBuf, String)> = Vec::new();
+        for c in collections {
+            let name = &c.collection.name;
+            let output_dir = PathBuf::from(&c.collection.output_dir);
+            for post in &c.posts {
+                if let Some(path) = post.get("path").and_then(|v| v.as_string()) {
+                    let title = post.get("title").and_then(|v| v.as_string());
+                    let source =
+                        format!("the post {} in {}", title.as_ref().unwrap_or(&path), name);
+                    outputs.push((path.into(), source));
+                }
+            }
+            if c.collection.index {
+                outputs.push((output_dir.join("index.html"), format!("the {} index", name)));
+            }
+            if c.collection.feed {
+                outputs.push((output_dir.join("atom.xml"), format!("the {} feed", name)));
+            }
+        }
+        for t in taxonomies {
+            let name = &t.taxonomy.name;
+            if let Some(overview_dir) = t.taxonomy.overview_dir() {
+                outputs.push((
+                    PathBuf::from(overview_dir).join("index.html"),
+                    format!("the index of all {}", name),
+                ));
+            }
+            for term in &t.terms {
+                let output_dir = PathBuf::from(&t.taxonomy.output_dir).join(&term.slug);
+                outputs.push((
+                    output_dir.join("index.html"),
+                    format!("the {} index of {}", name, term.name),
+                ));
+                if t.taxonomy.feed {
+                    outputs.push((
+                        output_dir.join("atom.xml"),
+                        format!("the {} feed of {}", name, term.name),
+                    ));
+                }
+            }
+        }
+        for s in series {
+            let mut index_path = PathBuf::from("series");
+            index_path.push(&s.slug);
+            index_path.push("index.html");
+            outputs.push((index_path, format!("the index of series {}", s.name)));
+        }
+
+        let mut sources: HashMap<&Path, &str> = HashMap::new();
+        for (path, source) in &outputs {
+            if let Some(other) = sources.insert(path, source) {
+                return Err(Error::DuplicateOutput(
+                    path.as_os_str().to_os_string(),
+                    format!("rendered from both {} and {}", other, source),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the path of the source directory of a collection.
+    fn collection_dir(&self, collection: &Collection) -> PathBuf {
+        let mut dir = PathBuf::new();
+        dir.push(&self.src_dir);
+        dir.push(&collection.source_dir);
+        dir
+    }
+
+    /// Find the collection whose source directory contains a file.
+    fn collection_of(&self, path: &Path) -> Option<&Collection> {
+        self.config
+            .collections
+            .iter()
+            .filter(|c| path.starts_with(self.collection_dir(c)))
+            .max_by_key(|c| self.collection_dir(c).components().count())
+    }
+
+    /// Get the path of the HTML file rendered from the source of a post,
+    /// relative to the destination directory.
+    fn dest_path(&self, path: &Path) -> Option<PathBuf> {
+        let collection = self.collection_of(path)?;
+        let relative = path.strip_prefix(self.collection_dir(collection)).ok()?;
+        let mut dest_path = PathBuf::from(&collection.output_dir);
+        dest_path.push(relative);
+        dest_path.set_extension("html");
+        Some(dest_path)
+    }
+
+    /// Find the bundle containing a file in a collection, which is the
+    /// nearest directory above it that contains an `index.md` or
+    /// `index.html` file.
+    fn bundle_dir(&self, path: &Path) -> Option<PathBuf> {
+        let content_dir = self.collection_dir(self.collection_of(path)?);
+        path.parent()?
+            .ancestors()
+            .take_while(|dir| dir.starts_with(&content_dir) && *dir != content_dir)
+            .find(|dir| dir.join("index.md").is_file() || dir.join("index.html").is_file())
+            .map(Path::to_path_buf)
+    }
+
+    /// Determine whether a file in a collection is the source of a post: a
+    /// Markdown or HTML file that is either the index of a bundle or not in
+    /// a bundle at all. Other files are resources that are copied to the
+    /// destination directory.
+    fn is_post_source(&self, path: &Path) -> bool {
+        let is_source = matches!(
+            path.extension().and_then(|s| s.to_str()),
+            Some("md") | Some("html")
+        );
+        let in_bundle = self.bundle_dir(path).is_some_and(|dir| {
+            path.parent() != Some(dir.as_path())
+                || path.file_stem().and_then(|s| s.to_str()) != Some("index")
         });
-        posts.reverse();
-        Ok(posts)
+        is_source && !in_bundle
+    }
+
+    /// Copy the files in a collection that are not posts, such as images,
+    /// to the same place in the output directory of the collection. The
+    /// files in a bundle are only copied if its post is published.
+    fn copy_post_resources(&self, collection: &Collection, posts: &[Post]) -> Result<usize> {
+        let posts_dir = self.collection_dir(collection);
+        if !posts_dir.is_dir() {
+            return Ok(0);
+        }
+        let mut count = 0_usize;
+        let published: Vec<String> = posts
+            .iter()
+            .filter_map(|p| p.get("path").and_then(|v| v.as_string()))
+            .collect();
+
+        for rde in fs::recursive_read_dir(&posts_dir)? {
+            let src_path = rde?.path();
+            if src_path.is_dir() || is_hidden(&src_path) || self.is_post_source(&src_path) {
+                continue;
+            }
+            let extension = src_path.extension().and_then(|s| s.to_str());
+            if let Some(dir) = self.bundle_dir(&src_path) {
+                let mut index = PathBuf::from(&collection.output_dir);
+                index.push(dir.strip_prefix(&posts_dir)?);
+                index.push("index.html");
+                if !published.contains(&index.to_string_lossy().into_owned()) {
+                    info!(
+                        "Skipping resource of unpublished post {}",
+                        src_path.display()
+                    );
+                    continue;
+                }
+            } else if extension.is_some_and(|e| TEXT_EXTENSIONS.contains(&e)) {
+                warn!(
+                    "Copying {} as a resource, as only .md and .html files are posts",
+                    src_path.display()
+                );
+            }
+            let mut dest_path = PathBuf::new();
+            dest_path.push(&self.dest_dir);
+            dest_path.push(&collection.output_dir);
+            dest_path.push(src_path.strip_prefix(&posts_dir)?);
+            if let Some(parent) = dest_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            info!("Copying {} to {}", src_path.display(), dest_path.display());
+            std::fs::copy(src_path, dest_path)?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Get the URL of a post from the path of its HTML file in the
+    /// destination directory. The URL of a post bundle is the URL of its
+    /// directory.
+    fn post_url(&self, dest_path: &Path) -> String {
+        let path = dest_path.to_string_lossy();
+        let path = if dest_path.file_name().and_then(|s| s.to_str()) == Some("index.html") {
+            path.trim_end_matches("index.html")
+        } else {
+            &path
+        };
+        format!("{}/{}", self.base_url, path)
+    }
+
+    /// Determine whether a post is published, based on its draft flag and
+    /// its publish and expiry dates.
+    fn is_published(&self, post: &Post) -> bool {
+        let draft = post.get("draft").and_then(|v| v.as_bool()) == Some(true);
+        let publish_date = post
+            .get("publish_date")
+            .or_else(|| post.get("date"))
+            .and_then(|v| v.as_datetime());
+        let expiry_date = post.get("expiry_date").and_then(|v| v.as_datetime());
+
+        (self.include_drafts || !draft)
+            && (self.include_future || publish_date.is_none_or(|d| d <= self.now))
+            && expiry_date.is_none_or(|d| d > self.now)
     }
 
-    /// Parse a post from the given [Path].
-    fn parse_post<P>(&self, path: P) -> Result<Post>
+    /// Parse a post from the given [Path], along with the links in its
+    /// source.
+    fn parse_post<P>(&self, path: P) -> Result<(Post, PostLinks)>
     where
         P: AsRef<Path>,
     {
@@ -464,34 +1513,156 @@ impl<'a> Bloggo<'a> {
         if buf.read_line(&mut line)? == 0 {
             return Err(Error::UnexpectedEOF(p.as_os_str().to_os_string()));
         }
+        let mut rest_of_file = String::new();
+        let mut front_matter_lines = 0;
+        let invalid = |e: Error| match e {
+            Error::Other(s) => Error::InvalidFrontMatter(p.into(), s),
+            e => e,
+        };
         let mut post = if line.starts_with("---") {
             debug!("parse_post: Parsing YAML front matter.");
-            let front_matter = read_until(&mut buf, "---")?;
-            if let Value::Map(map) = parse_yaml_data(front_matter.as_str())? {
+            let front_matter = read_until(&mut buf, "---").map_err(invalid)?;
+            front_matter_lines = front_matter.lines().count() + 2;
+            if let Value::Map(map) = parse_yaml_data(front_matter.as_str()).map_err(invalid)? {
+                Ok(map)
+            } else {
+                Err(Error::InvalidFrontMatter(
+                    p.into(),
+                    String::from("Parsed YAML is not a mapping."),
+                ))
+            }
+        } else if line.starts_with("+++") {
+            debug!("parse_post: Parsing TOML front matter.");
+            let front_matter = read_until(&mut buf, "+++").map_err(invalid)?;
+            front_matter_lines = front_matter.lines().count() + 2;
+            if let Value::Map(map) = parse_toml_data(front_matter.as_str()).map_err(invalid)? {
                 Ok(map)
             } else {
-                Err(Error::Other("Parsed YAML is not a mapping.".to_string()))
+                Err(Error::InvalidFrontMatter(
+                    p.into(),
+                    String::from("Parsed TOML is not a table."),
+                ))
+            }
+        } else if line.starts_with('{') && !line.starts_with("{{") {
+            // A post that starts with a shortcode has no front matter.
+            debug!("parse_post: Parsing JSON front matter.");
+            buf.read_to_string(&mut line)?;
+            let (front_matter, rest) = parse_json_data(line.as_str()).map_err(invalid)?;
+            front_matter_lines = line[..line.len() - rest.len()].matches('\n').count();
+            rest_of_file.push_str(rest);
+            if let Value::Map(map) = front_matter {
+                Ok(map)
+            } else {
+                Err(Error::InvalidFrontMatter(
+                    p.into(),
+                    String::from("Parsed JSON is not an object."),
+                ))
             }
         } else {
-            Err(Error::Other("Missing front matter.".to_string()))
+            debug!("parse_post: No front matter.");
+            rest_of_file.push_str(&line);
+            Ok(Post::new())
         }?;
-        let mut rest_of_file = String::new();
+        let infer_title = post.is_empty();
+        parse_dates(&mut post, p, self.config.timezone)?;
         buf.read_to_string(&mut rest_of_file)?;
+        rest_of_file =
+            shortcode::expand_shortcodes(&rest_of_file, &self.handlebars).map_err(|e| {
+                let line = front_matter_lines + rest_of_file[..e.offset].matches('\n').count() + 1;
+                Error::InvalidShortcode(p.into(), line, e.message)
+            })?;
+
+        let separator = self.config.excerpt_separator.as_str();
+        let excerpt = rest_of_file
+            .split_once(separator)
+            .map(|(before, _)| String::from(before));
+        if excerpt.is_some() {
+            rest_of_file = rest_of_file.replacen(separator, "", 1);
+        }
 
-        let mut text = String::with_capacity(rest_of_file.len());
-        if p.extension().and_then(|s| s.to_str()) == Some("md") {
-            let mut options = Options::all();
-            options.remove(Options::ENABLE_SMART_PUNCTUATION);
-            let parser = Parser::new_ext(&rest_of_file, options);
-            html::push_html(&mut text, parser);
-        } else {
-            text = rest_of_file;
+        let mut options = self.config.markdown_options;
+        if let Some(extensions) = post.get("markdown") {
+            markdown::apply_extensions(&mut options, extensions)
+                .map_err(|e| Error::Other(format!("Invalid markdown in {}: {}", p.display(), e)))?;
+        }
+
+        let dest_path_buf = self
+            .dest_path(p)
+            .ok_or_else(|| Error::Other(format!("{} is not in a collection", p.display())))?;
+        let url = self.post_url(&dest_path_buf);
+
+        // Links to resources in a post bundle are made absolute, so that they
+        // also work when the post is included in an index.
+        let bundle_dir = self.bundle_dir(p);
+        let is_bundle = bundle_dir.is_some();
+        let mut linked_posts = Vec::new();
+        let mut rendered_links = Vec::new();
+        let mut resolve_link = |link: &str| {
+            let path = links::percent_decode(link);
+            let rendered = if path.ends_with(".md") {
+                let (url, target) = self.resolve_post_link(p, &path)?;
+                linked_posts.push((String::from(link), target));
+                url
+            } else if is_bundle {
+                format!("{}{}", url, link)
+            } else {
+                String::from(link)
+            };
+            rendered_links.push((String::from(link), rendered.clone()));
+            Some(rendered)
+        };
+
+        let is_markdown = p.extension().and_then(|s| s.to_str()) == Some("md");
+        let mut render = |source: String| {
+            if is_markdown {
+                render_markdown(&source, options, infer_title, &mut resolve_link)
+            } else {
+                Markdown {
+                    html: source,
+                    title: None,
+                    toc: Vec::new(),
+                    broken_links: Vec::new(),
+                }
+            }
+        };
+        let Markdown {
+            html: text,
+            title,
+            toc,
+            broken_links,
+        } = render(rest_of_file);
+        if let Some(link) = broken_links.first() {
+            return Err(Error::BrokenLink(p.into(), link.clone()));
         }
+        if let Some(title) = title {
+            post.insert("title".into(), title.into());
+        }
+        post.insert("toc".into(), toc.into());
         if !post.contains_key("abstract") {
-            if let Some(p) = extract_first_paragraph(&text) {
-                post.insert("abstract".into(), p.into());
+            let abstract_text = match excerpt {
+                Some(excerpt) => Some(render(excerpt).html),
+                None => extract_first_paragraph(&text),
+            };
+            if let Some(a) = abstract_text {
+                post.insert("abstract".into(), a.into());
             }
         }
+        if !post.contains_key("summary") {
+            let summary_html = post
+                .get("abstract")
+                .and_then(|v| v.as_string())
+                .unwrap_or_else(|| text.clone());
+            let summary = text::truncate_words(
+                &text::html_to_text(&summary_html),
+                self.config.summary_words,
+            );
+            post.insert("summary".into(), summary.into());
+        }
+
+        let word_count = text::html_to_text(&text).split_whitespace().count();
+        let reading_time = word_count.div_ceil(self.config.words_per_minute);
+        post.insert("word_count".into(), (word_count as i64).into());
+        post.insert("reading_time".into(), (reading_time as i64).into());
 
         // Replace a tags String with a single element tags Array
         if let Some(Value::String(_)) = post.get("tags") {
@@ -502,30 +1673,81 @@ impl<'a> Bloggo<'a> {
 
         post.insert("text".into(), text.into());
 
-        let mut dest_path_buf = p
-            .strip_prefix(&self.src_dir)?
-            .strip_prefix("posts")?
-            .to_path_buf();
-        dest_path_buf.set_extension("html");
-
         let cows = dest_path_buf.to_string_lossy();
         let filename: &str = cows.borrow();
         post.insert("path".into(), filename.into());
-
-        let mut url = String::from(&self.base_url);
-        url.push('/');
-        url.push_str(filename);
         post.insert("url".into(), url.into());
         if !post.contains_key("date") {
-            if let Some(date) = extract_date_from_str(filename) {
-                let formatted = format!("{}", date.format("%+"));
-                post.insert("date".into(), formatted.into());
+            // The date is taken from the name of the source file, or of the
+            // directory of a bundle, as the destination path starts with the
+            // output directory of the collection.
+            let name = match &bundle_dir {
+                Some(dir) => dir.file_name(),
+                None => p.file_stem(),
+            };
+            if let Some(date) = name
+                .and_then(|s| s.to_str())
+                .and_then(|s| extract_date_from_str(s, self.config.timezone))
+            {
+                post.insert("date".into(), date.into());
             }
         }
-        Ok(post)
+        let links = PostLinks {
+            source: p.to_path_buf(),
+            page: dest_path_buf,
+            posts: linked_posts,
+            rendered: rendered_links,
+        };
+        Ok((post, links))
+    }
+
+    /// Resolve a relative link from the post at the given path to the URL of
+    /// the post it refers to, if that post exists, along with the path of its
+    /// source.
+    fn resolve_post_link(&self, post_path: &Path, link: &str) -> Option<(String, PathBuf)> {
+        let mut target = post_path.parent()?.to_path_buf();
+        for component in Path::new(link).components() {
+            match component {
+                Component::ParentDir => {
+                    target.pop();
+                }
+                Component::Normal(name) => target.push(name),
+                _ => {}
+            }
+        }
+        if !target.is_file() || !self.is_post_source(&target) {
+            return None;
+        }
+        let url = self.post_url(&self.dest_path(&target)?);
+        Some((url, target))
     }
 }
 
+/// Get the slug of the name of a tag or series, checking that it is not
+/// empty and that it differs from the slugs of the other names, as it is
+/// used as the name of a directory.
+fn insert_slug<'b>(
+    slugs: &mut HashMap<String, &'b str>,
+    name: &'b str,
+) -> std::result::Result<String, String> {
+    let slug = text::slugify(name);
+    if slug.is_empty() {
+        return Err(String::from("has no letters or digits for its slug"));
+    }
+    if let Some(other) = slugs.insert(slug.clone(), name) {
+        return Err(format!("has the same slug {} as {}", slug, other));
+    }
+    Ok(slug)
+}
+
+/// Determine whether a file is hidden, because its name starts with a dot.
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|os| os.to_str())
+        .map(|s| s.starts_with('.'))
+        .unwrap_or(false)
+}
+
 /// Find the content between `<p>` and `</p>`, if it exists.
 ///
 ///# Example
@@ -546,27 +1768,99 @@ fn extract_first_paragraph(text: &str) -> Option<String> {
     })
 }
 
-/// Attempt to extract a date from the first ten characters of a string,
-/// if it is formatted as YYYY-mm-dd.
-///
-/// The date will have a time of midnight, UTC
+/// The extensions of text files that are not posts, but may have been meant
+/// to be.
+const TEXT_EXTENSIONS: [&str; 7] = ["markdown", "mdown", "mkd", "mkdn", "text", "txt", "htm"];
+
+/// The front matter properties that contain dates.
+const DATE_PROPERTIES: [&str; 4] = ["date", "updated", "publish_date", "expiry_date"];
+
+/// Replace the [String] values of the date properties of a post with
+/// [Value::DateTime] values, failing if any of them cannot be parsed.
+fn parse_dates(post: &mut Post, path: &Path, tz: Tz) -> Result<()> {
+    for key in DATE_PROPERTIES {
+        let datetime = match post.get(key) {
+            None | Some(Value::DateTime(_)) => continue,
+            Some(Value::String(s)) => parse_date(s, tz)
+                .ok_or_else(|| Error::InvalidDate(path.into(), format!("{}: {}", key, s)))?,
+            Some(_) => {
+                return Err(Error::InvalidDate(
+                    path.into(),
+                    format!("{}: not a string", key),
+                ))
+            }
+        };
+        post.insert(key.into(), datetime.into());
+    }
+    Ok(())
+}
+
+/// Formats of dates and times without an offset that are accepted by
+/// [parse_date].
+const NAIVE_DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// Parse a date and time in one of the RFC 3339, ISO 8601 or RFC 2822
+/// formats, or a date with an optional time but no offset. Dates without an
+/// offset are in the given time zone, and dates without a time are at
+/// midnight.
 ///
 ///# Example
+///
 /// ```compile_fail
-/// use bloggo::extract_date_from_str;
+/// use bloggo::parse_date;
 ///
 /// assert_eq!(
-///     "2023-02-26T00:00:00Z".parse::<DateTime<Utc>>().ok(),
-///     extract_date_from_str("2023-02-26-blah blah blah")
+///     DateTime::parse_from_rfc3339("2023-04-05T14:00:00+02:00").ok(),
+///     parse_date("2023-04-05 14:00", chrono_tz::Europe::Paris)
 /// );
 /// ```
-fn extract_date_from_str(s: &str) -> Option<DateTime<Utc>> {
+fn parse_date(s: &str, tz: Tz) -> Option<DateTime<FixedOffset>> {
+    let s = s.trim();
+    DateTime::parse_from_rfc3339(s)
+        .or_else(|_| DateTime::parse_from_str(s, "%+"))
+        .or_else(|_| DateTime::parse_from_rfc2822(s))
+        .ok()
+        .or_else(|| {
+            NAIVE_DATETIME_FORMATS
+                .iter()
+                .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+                .or_else(|| {
+                    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                        .ok()
+                        .and_then(|d| d.and_hms_opt(0, 0, 0))
+                })
+                .and_then(|dt| tz.from_local_datetime(&dt).earliest())
+                .map(|dt| dt.fixed_offset())
+        })
+}
+
+/// Attempt to extract a date from the first ten characters of a string,
+/// if it is formatted as YYYY-mm-dd.
+///
+/// The date will have a time of midnight in the given time zone.
+///
+///# Example
+/// ```compile_fail
+/// use bloggo::extract_date_from_str;
+///
+/// assert_eq!(
+///     DateTime::parse_from_rfc3339("2023-02-26T00:00:00Z").ok(),
+///     extract_date_from_str("2023-02-26-blah blah blah", Tz::UTC)
+/// );
+/// ```
+fn extract_date_from_str(s: &str, tz: Tz) -> Option<DateTime<FixedOffset>> {
     let mut truncated = String::from(s);
     truncated.truncate(10);
     NaiveDate::parse_from_str(truncated.as_str(), "%Y-%m-%d")
         .ok()
         .and_then(|d| d.and_hms_opt(0, 0, 0))
-        .map(|dt| DateTime::from_naive_utc_and_offset(dt, Utc))
+        .and_then(|dt| tz.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.fixed_offset())
 }
 
 /// Parse a YAML [str] into a [Value].
@@ -576,6 +1870,34 @@ fn parse_yaml_data(yaml: &str) -> Result<Value> {
     yval.try_into()
 }
 
+/// Parse a TOML [str] into a [Value].
+fn parse_toml_data(toml: &str) -> Result<Value> {
+    let tval = toml::from_str::<toml::Value>(toml)
+        .map_err(|e| Error::Other(format!("TOML deserialization failure: {}", e)))?;
+    Value::try_from(tval)
+}
+
+/// Parse the JSON value at the start of a [str] into a [Value], returning it
+/// along with the remainder of the [str] that follows the value.
+///
+///# Example
+///
+/// ```compile_fail
+/// use bloggo::parse_json_data;
+///
+/// let (value, rest) = parse_json_data("{\"title\": \"A Title\"}\nThe text.").unwrap();
+/// assert_eq!("\nThe text.", rest);
+/// ```
+fn parse_json_data(json: &str) -> Result<(Value, &str)> {
+    let mut stream = serde_json::Deserializer::from_str(json).into_iter::<serde_json::Value>();
+    let jval = stream
+        .next()
+        .ok_or_else(|| Error::Other("Unexpected end of file.".to_string()))?
+        .map_err(|e| Error::Other(format!("JSON deserialization failure: {}", e)))?;
+    let rest = &json[stream.byte_offset()..];
+    Ok((Value::try_from(jval)?, rest))
+}
+
 /// Read a [BufRead] into a [String] until a linke with the given prefix
 ///
 ///# Example
@@ -625,6 +1947,10 @@ pub struct Builder {
     src_dir: String,
     dest_dir: String,
     base_url: String,
+    include_drafts: bool,
+    include_future: bool,
+    link_check: bool,
+    now: Option<DateTime<Utc>>,
 }
 
 impl Builder {
@@ -635,6 +1961,10 @@ impl Builder {
             src_dir: String::from("src/"),
             dest_dir: String::from("dest/"),
             base_url: String::from(""),
+            include_drafts: false,
+            include_future: false,
+            link_check: false,
+            now: None,
         }
     }
 
@@ -655,9 +1985,43 @@ impl Builder {
         self
     }
 
+    /// Set whether posts marked as drafts are included in the site.
+    pub fn include_drafts(mut self, include_drafts: bool) -> Self {
+        self.include_drafts = include_drafts;
+        self
+    }
+
+    /// Set whether posts with a publish date in the future are included in
+    /// the site.
+    pub fn include_future(mut self, include_future: bool) -> Self {
+        self.include_future = include_future;
+        self
+    }
+
+    /// Set whether the links in the built site are checked at the end of the
+    /// build, which fails if any are broken.
+    pub fn with_link_check(mut self, link_check: bool) -> Self {
+        self.link_check = link_check;
+        self
+    }
+
+    /// Set the time the site is built, which is compared to the publish and
+    /// expiry dates of posts. The default is the current time.
+    pub fn now(mut self, now: DateTime<Utc>) -> Self {
+        self.now = Some(now);
+        self
+    }
+
     /// Build a Bloggo struct with the previously configured values.
     pub fn build<'a>(self) -> Bloggo<'a> {
-        Bloggo::new(self.src_dir, self.dest_dir, self.base_url)
+        let mut bloggo = Bloggo::new(self.src_dir, self.dest_dir, self.base_url);
+        bloggo.include_drafts = self.include_drafts;
+        bloggo.include_future = self.include_future;
+        bloggo.link_check = self.link_check;
+        if let Some(now) = self.now {
+            bloggo.now = now;
+        }
+        bloggo
     }
 }
 
@@ -670,8 +2034,12 @@ impl Default for Builder {
 /// Struct that holds information about a single tag.
 struct Tag<'a> {
     name: &'a str,
+    slug: String,
     index_url: String,
     count: usize,
+    weight: usize,
+    posts: &'a Vec<&'a Post>,
+    recent_posts: Vec<&'a Post>,
 }
 
 impl<'a> Serialize for Tag<'a> {
@@ -679,19 +2047,223 @@ impl<'a> Serialize for Tag<'a> {
     where
         S: Serializer,
     {
-        let mut s = serializer.serialize_map(Some(3))?;
+        let mut s = serializer.serialize_map(Some(6))?;
         s.serialize_entry("name", self.name)?;
+        s.serialize_entry("slug", &self.slug)?;
         s.serialize_entry("index_url", &self.index_url)?;
         s.serialize_entry("count", &self.count)?;
+        s.serialize_entry("weight", &self.weight)?;
+        s.serialize_entry(
+            "recent_posts",
+            &post_refs(self.recent_posts.iter().copied()),
+        )?;
+        s.end()
+    }
+}
+
+/// The terms of a taxonomy used in the site.
+struct TaxonomyTerms<'a> {
+    taxonomy: &'a Taxonomy,
+    terms: Vec<Tag<'a>>,
+}
+
+/// The published posts of a collection.
+struct CollectionPosts<'a> {
+    collection: &'a Collection,
+    posts: Vec<Post>,
+}
+
+/// The links in the source of a post, recorded while it is parsed.
+struct PostLinks {
+    /// The source file of the post.
+    source: PathBuf,
+
+    /// The path of the generated page, relative to the destination
+    /// directory.
+    page: PathBuf,
+
+    /// The links to other posts, along with the source files of those
+    /// posts.
+    posts: Vec<(String, PathBuf)>,
+
+    /// The relative links, as they appear in the source, along with the
+    /// destinations they were rendered to.
+    rendered: Vec<(String, String)>,
+}
+
+/// A series of posts, in the order in which they are meant to be read.
+struct Series<'a> {
+    name: &'a str,
+    slug: String,
+    index_url: String,
+    parts: Vec<&'a Post>,
+}
+
+/// A series, along with the position in it of the post being rendered.
+struct SeriesContext<'a> {
+    series: &'a Series<'a>,
+    position: Option<usize>,
+}
+
+impl<'a> Serialize for SeriesContext<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let parts = &self.series.parts;
+        let mut s = serializer.serialize_map(None)?;
+        s.serialize_entry("name", self.series.name)?;
+        s.serialize_entry("index_url", &self.series.index_url)?;
+        s.serialize_entry("parts", &post_refs(parts.iter().copied()))?;
+        s.serialize_entry("count", &parts.len())?;
+        if let Some(i) = self.position {
+            s.serialize_entry("position", &(i + 1))?;
+            if let Some(prev) = i.checked_sub(1).map(|j| parts[j]) {
+                s.serialize_entry("prev", &PostRef(prev))?;
+            }
+            if let Some(next) = parts.get(i + 1) {
+                s.serialize_entry("next", &PostRef(next))?;
+            }
+        }
+        s.end()
+    }
+}
+
+/// The posts before and after a post, in a collection or a tag.
+struct Neighbours<'a> {
+    prev: Option<&'a Post>,
+    next: Option<&'a Post>,
+}
+
+impl<'a> Serialize for Neighbours<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_map(None)?;
+        if let Some(prev) = self.prev {
+            s.serialize_entry("prev", &PostRef(prev))?;
+        }
+        if let Some(next) = self.next {
+            s.serialize_entry("next", &PostRef(next))?;
+        }
+        s.end()
+    }
+}
+
+/// A link to a post from another page, such as a neighbour of the post
+/// being rendered or a post in the lists of the whole site, which only has
+/// the properties needed to link to it, so that the whole text of the post
+/// is not repeated in every page.
+struct PostRef<'a>(&'a Post);
+
+impl<'a> Serialize for PostRef<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_map(None)?;
+        for key in ["title", "url", "date"] {
+            if let Some(value) = self.0.get(key) {
+                s.serialize_entry(key, value)?;
+            }
+        }
+        s.end()
+    }
+}
+
+/// Get links to each of a list of posts.
+fn post_refs<'a>(posts: impl IntoIterator<Item = &'a Post>) -> Vec<PostRef<'a>> {
+    posts.into_iter().map(PostRef).collect()
+}
+
+/// The post being rendered, along with its neighbours in its collection
+/// and in each of its tags.
+struct PostContext<'a> {
+    post: &'a Post,
+    neighbours: Neighbours<'a>,
+    tag_navigation: HashMap<&'a str, Neighbours<'a>>,
+    related: Vec<&'a Post>,
+}
+
+/// The data about the whole site, which is available to every template.
+#[derive(Clone, Copy)]
+struct Site<'a> {
+    posts: &'a Vec<Post>,
+    taxonomies: &'a [TaxonomyTerms<'a>],
+    collections: &'a [CollectionPosts<'a>],
+    series: &'a [Series<'a>],
+}
+
+impl<'a> Site<'a> {
+    /// Get the posts of the `pages` collection.
+    fn pages(&self) -> Option<&'a Vec<Post>> {
+        self.collections
+            .iter()
+            .find(|c| c.collection.name == "pages")
+            .map(|c| &c.posts)
+    }
+
+    /// Get links to the posts of each collection other than `posts` and
+    /// `pages` by the name of the collection.
+    fn collection_posts(&self) -> HashMap<&'a str, Vec<PostRef<'a>>> {
+        self.collections
+            .iter()
+            .filter(|c| c.collection.name != "posts" && c.collection.name != "pages")
+            .map(|c| (c.collection.name.as_str(), post_refs(&c.posts)))
+            .collect()
+    }
+
+    /// Get the terms of each taxonomy other than `tags` by the name of the
+    /// taxonomy.
+    fn taxonomy_terms(&self) -> HashMap<&'a str, &'a Vec<Tag<'a>>> {
+        self.taxonomies
+            .iter()
+            .filter(|t| t.taxonomy.name != "tags")
+            .map(|t| (t.taxonomy.name.as_str(), &t.terms))
+            .collect()
+    }
+
+    /// Get the terms of a taxonomy.
+    fn terms(&self, taxonomy: &str) -> Option<&'a Vec<Tag<'a>>> {
+        self.taxonomies
+            .iter()
+            .find(|t| t.taxonomy.name == taxonomy)
+            .map(|t| &t.terms)
+    }
+
+    /// Get the terms of the `tags` taxonomy.
+    fn tags(&self) -> &'a [Tag<'a>] {
+        self.taxonomies
+            .iter()
+            .find(|t| t.taxonomy.name == "tags")
+            .map_or(&[], |t| &t.terms)
+    }
+}
+
+impl<'a> Serialize for Site<'a> {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut s = serializer.serialize_map(Some(4))?;
+        s.serialize_entry("posts", &post_refs(self.posts))?;
+        s.serialize_entry("pages", &self.pages().map(post_refs))?;
+        s.serialize_entry("collections", &self.collection_posts())?;
+        s.serialize_entry("taxonomies", &self.taxonomy_terms())?;
         s.end()
     }
 }
 
 /// Structure to hold the data values rendered by Handlebars
 struct RenderContext<'a> {
+    collection: Option<&'a str>,
+    taxonomy: Option<&'a str>,
     tag: Option<&'a str>,
-    tags: &'a Vec<Tag<'a>>,
+    series: Option<SeriesContext<'a>>,
+    post: Option<PostContext<'a>>,
     posts: &'a Vec<&'a Post>,
+    site: Site<'a>,
 }
 
 impl<'a> Serialize for RenderContext<'a> {
@@ -699,11 +2271,43 @@ impl<'a> Serialize for RenderContext<'a> {
     where
         S: Serializer,
     {
-        let len: usize = 2 + usize::from(self.tag.is_some());
-        let mut s = serializer.serialize_map(Some(len))?;
-        self.tag.map(|t| s.serialize_entry("tag", t));
-        s.serialize_entry("tags", self.tags)?;
+        let total = |key| -> i64 {
+            self.posts
+                .iter()
+                .filter_map(|p| p.get(key).and_then(|v| v.as_i64()))
+                .sum()
+        };
+
+        let mut s = serializer.serialize_map(None)?;
+        if let Some(collection) = self.collection {
+            s.serialize_entry("collection", collection)?;
+        }
+        if let Some(taxonomy) = self.taxonomy {
+            s.serialize_entry("taxonomy", taxonomy)?;
+            s.serialize_entry("terms", &self.site.terms(taxonomy))?;
+        }
+        if let Some(tag) = self.tag {
+            s.serialize_entry("tag", tag)?;
+        }
+        if let Some(series) = &self.series {
+            s.serialize_entry("series", series)?;
+        }
+        if let Some(post) = &self.post {
+            s.serialize_entry("post", post.post)?;
+            if let Some(prev) = post.neighbours.prev {
+                s.serialize_entry("prev", &PostRef(prev))?;
+            }
+            if let Some(next) = post.neighbours.next {
+                s.serialize_entry("next", &PostRef(next))?;
+            }
+            s.serialize_entry("tag_navigation", &post.tag_navigation)?;
+            s.serialize_entry("related", &post_refs(post.related.iter().copied()))?;
+        }
+        s.serialize_entry("site", &self.site)?;
+        s.serialize_entry("tags", self.site.tags())?;
         s.serialize_entry("posts", self.posts)?;
+        s.serialize_entry("word_count", &total("word_count"))?;
+        s.serialize_entry("reading_time", &total("reading_time"))?;
         s.end()
     }
 }
@@ -711,6 +2315,363 @@ impl<'a> Serialize for RenderContext<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use tempfile::TempDir;
+
+    /// Create a Bloggo instance whose source directory is a new temporary
+    /// directory, which is removed when the returned [TempDir] is dropped.
+    fn test_bloggo<'a>() -> (TempDir, Bloggo<'a>) {
+        let dir = TempDir::with_prefix("bloggo-test-").unwrap();
+        let src_dir = dir.path();
+        fs::create_dir_all(src_dir.join("posts")).unwrap();
+        let bloggo = Builder::new()
+            .src_dir(src_dir.to_string_lossy())
+            .dest_dir(src_dir.join("build").to_string_lossy())
+            .build();
+        (dir, bloggo)
+    }
+
+    /// Write a post to the source directory of a Bloggo instance, returning
+    /// its path.
+    fn write_test_post(bloggo: &Bloggo, filename: &str, source: &str) -> PathBuf {
+        let mut path = PathBuf::from(&bloggo.src_dir);
+        path.push("posts");
+        path.push(filename);
+        std::fs::write(&path, source).unwrap();
+        path
+    }
+
+    #[test]
+    fn parse_post_excerpt_separator() {
+        let (_dir, bloggo) = test_bloggo();
+        let path = write_test_post(
+            &bloggo,
+            "2023-04-05_excerpt.md",
+            "---\ntitle: Excerpt\n---\n![A photo](photo.jpg)\n\n<!--more-->\n\nThe rest.\n",
+        );
+        let (post, _) = bloggo.parse_post(path).unwrap();
+        assert_eq!(
+            Some("<p><img src=\"photo.jpg\" alt=\"A photo\" /></p>\n".to_string()),
+            post["abstract"].as_string()
+        );
+        assert_eq!(
+            Some(
+                "<p><img src=\"photo.jpg\" alt=\"A photo\" /></p>\n<p>The rest.</p>\n".to_string()
+            ),
+            post["text"].as_string()
+        );
+    }
+
+    #[test]
+    fn parse_post_without_excerpt_separator() {
+        let (_dir, bloggo) = test_bloggo();
+        let path = write_test_post(
+            &bloggo,
+            "2023-04-05_excerpt.md",
+            "---\ntitle: Excerpt\n---\nFirst.\n\nSecond.\n",
+        );
+        let (post, _) = bloggo.parse_post(path).unwrap();
+        assert_eq!(
+            Some("<p>First.</p>".to_string()),
+            post["abstract"].as_string()
+        );
+        assert_eq!(Some("First.".to_string()), post["summary"].as_string());
+    }
+
+    #[test]
+    fn parse_post_shortcode_error_line() {
+        let (_dir, bloggo) = test_bloggo();
+        let path = write_test_post(
+            &bloggo,
+            "2023-04-05_shortcode.md",
+            "---\ntitle: Shortcode\n---\nFirst.\n\n{{< missing >}}\n",
+        );
+        assert!(matches!(
+            bloggo.parse_post(path),
+            Err(Error::InvalidShortcode(_, 6, _))
+        ));
+    }
+
+    #[test]
+    fn parse_post_invalid_front_matter() {
+        let (_dir, bloggo) = test_bloggo();
+        for (filename, source) in [
+            ("yaml.md", "---\ntitle: [Unclosed\n---\n"),
+            ("toml.md", "+++\ntitle = \n+++\n"),
+            ("json.md", "{\"title\": }\n"),
+            ("list.md", "---\n- A list\n---\n"),
+        ] {
+            let path = write_test_post(&bloggo, filename, source);
+            match bloggo.parse_post(&path) {
+                Err(Error::InvalidFrontMatter(p, _)) => assert_eq!(path.as_os_str(), p),
+                result => panic!("expected invalid front matter, got {:?}", result.err()),
+            }
+        }
+    }
+
+    #[test]
+    fn parse_post_starting_with_shortcode() {
+        let (_dir, mut bloggo) = test_bloggo();
+        bloggo
+            .handlebars
+            .register_template_string("shortcodes/figure", "<img src=\"{{src}}\">")
+            .unwrap();
+        let path = write_test_post(
+            &bloggo,
+            "2023-04-05_figure.md",
+            "{{< figure src=\"a.jpg\" >}}\n\nThe text.\n",
+        );
+        let (post, _) = bloggo.parse_post(path).unwrap();
+        assert_eq!(
+            Some("<img src=\"a.jpg\">\n<p>The text.</p>\n".to_string()),
+            post["text"].as_string()
+        );
+    }
+
+    #[test]
+    fn parse_post_links_to_posts() {
+        let (_dir, mut bloggo) = test_bloggo();
+        bloggo.base_url = String::from("https://example.com");
+        write_test_post(&bloggo, "2023-03-01_other.md", "Other.\n");
+        let path = write_test_post(
+            &bloggo,
+            "2023-04-05_links.md",
+            "See [the other post](2023-03-01_other.md#clue).\n",
+        );
+        let (post, _) = bloggo.parse_post(path).unwrap();
+        assert_eq!(
+            Some(
+                "<p>See <a href=\"https://example.com/2023-03-01_other.html#clue\">the other post</a>.</p>\n"
+                    .to_string()
+            ),
+            post["text"].as_string()
+        );
+
+        let path = write_test_post(&bloggo, "2023-04-06_broken.md", "[Gone](gone.md)\n");
+        assert!(matches!(
+            bloggo.parse_post(path),
+            Err(Error::BrokenLink(_, link)) if link == "gone.md"
+        ));
+    }
+
+    #[test]
+    fn parse_post_links_with_query_and_escapes() {
+        let (_dir, bloggo) = test_bloggo();
+        let other = write_test_post(&bloggo, "my post.md", "Other.\n");
+        let path = write_test_post(
+            &bloggo,
+            "2023-04-05_links.md",
+            "[a](my%20post.md) [b](my%20post.md?x=1#clue)\n",
+        );
+        let (post, links) = bloggo.parse_post(path).unwrap();
+        assert_eq!(
+            Some(
+                "<p><a href=\"/my%20post.html\">a</a> \
+                 <a href=\"/my%20post.html?x=1#clue\">b</a></p>\n"
+                    .to_string()
+            ),
+            post["text"].as_string()
+        );
+        assert_eq!(
+            vec![
+                (String::from("my%20post.md"), other.clone()),
+                (String::from("my%20post.md"), other)
+            ],
+            links.posts
+        );
+    }
+
+    #[test]
+    fn check_post_links_unpublished() {
+        let (_dir, bloggo) = test_bloggo();
+        write_test_post(
+            &bloggo,
+            "2023-03-01_draft.md",
+            "---\ndraft: true\n---\nDraft.\n",
+        );
+        write_test_post(&bloggo, "2023-04-05_links.md", "[a](2023-03-01_draft.md)\n");
+        let (_, links) = bloggo
+            .parse_collection(&bloggo.config.collections[0])
+            .unwrap();
+        assert!(matches!(
+            bloggo.check_post_links(&links),
+            Err(Error::BrokenLink(_, message)) if message.starts_with("2023-03-01_draft.md")
+        ));
+
+        let bloggo = Builder::new()
+            .src_dir(&bloggo.src_dir)
+            .include_drafts(true)
+            .build();
+        let (_, links) = bloggo
+            .parse_collection(&bloggo.config.collections[0])
+            .unwrap();
+        assert!(bloggo.check_post_links(&links).is_ok());
+    }
+
+    #[test]
+    fn parse_post_bundle() {
+        let (_dir, bloggo) = test_bloggo();
+        let bundle_dir = Path::new(&bloggo.src_dir).join("posts/2023-04-05_trip");
+        fs::create_dir_all(&bundle_dir).unwrap();
+        std::fs::write(bundle_dir.join("photo.jpg"), "").unwrap();
+        std::fs::write(bundle_dir.join("notes.md"), "").unwrap();
+        let path = write_test_post(
+            &bloggo,
+            "2023-04-05_trip/index.md",
+            "---\ntitle: Trip\n---\n![A photo](photo.jpg)\n",
+        );
+        assert!(bloggo.is_post_source(&path));
+        assert!(!bloggo.is_post_source(&bundle_dir.join("photo.jpg")));
+        assert!(!bloggo.is_post_source(&bundle_dir.join("notes.md")));
+
+        let (post, _) = bloggo.parse_post(path).unwrap();
+        assert_eq!(
+            Some("/2023-04-05_trip/".to_string()),
+            post["url"].as_string()
+        );
+        assert_eq!(
+            Some("<p><img src=\"/2023-04-05_trip/photo.jpg\" alt=\"A photo\" /></p>\n".to_string()),
+            post["text"].as_string()
+        );
+    }
+
+    #[test]
+    fn find_broken_links_in_source() {
+        let (_dir, bloggo) = test_bloggo();
+        let bundle_dir = Path::new(&bloggo.src_dir).join("posts/2023-04-05_trip");
+        fs::create_dir_all(&bundle_dir).unwrap();
+        std::fs::write(
+            bundle_dir.join("index.md"),
+            "# Trip\n\n![Moor](moor%20view.jpg)\n\n![Missing](missing.jpg)\n",
+        )
+        .unwrap();
+        std::fs::write(bundle_dir.join("moor view.jpg"), "").unwrap();
+        let (posts, links) = bloggo
+            .parse_collection(&bloggo.config.collections[0])
+            .unwrap();
+        bloggo
+            .copy_post_resources(&bloggo.config.collections[0], &posts)
+            .unwrap();
+        let page = Path::new(&bloggo.dest_dir).join("2023-04-05_trip/index.html");
+        std::fs::write(&page, posts[0]["text"].as_string().unwrap()).unwrap();
+
+        assert_eq!(
+            vec![BrokenLink {
+                file: bundle_dir.join("index.md"),
+                line: 5,
+                link: String::from("missing.jpg"),
+            }],
+            bloggo.find_broken_links(&links).unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_collection_weight_order() {
+        let (_dir, bloggo) = test_bloggo();
+        let pages_dir = Path::new(&bloggo.src_dir).join("pages");
+        fs::create_dir_all(&pages_dir).unwrap();
+        std::fs::write(
+            pages_dir.join("about.md"),
+            "---\ntitle: About\nweight: 2\n---\n",
+        )
+        .unwrap();
+        std::fs::write(pages_dir.join("colophon.md"), "---\ntitle: Colophon\n---\n").unwrap();
+        std::fs::write(
+            pages_dir.join("contact.md"),
+            "---\ntitle: Contact\nweight: 1\n---\n",
+        )
+        .unwrap();
+
+        let (pages, _) = bloggo
+            .parse_collection(&bloggo.config.collections[1])
+            .unwrap();
+        let titles: Vec<String> = pages
+            .iter()
+            .filter_map(|p| p["title"].as_string())
+            .collect();
+        assert_eq!(vec!["Colophon", "Contact", "About"], titles);
+        assert_eq!(Some("/about.html".to_string()), pages[2]["url"].as_string());
+    }
+
+    #[test]
+    fn check_output_paths_collisions() {
+        let (_dir, bloggo) = test_bloggo();
+        let pages_dir = Path::new(&bloggo.src_dir).join("pages");
+        fs::create_dir_all(&pages_dir).unwrap();
+        write_test_post(&bloggo, "about.md", "---\ntitle: About the blog\n---\n");
+        let page = pages_dir.join("about.md");
+        std::fs::write(&page, "---\ntitle: About\n---\n").unwrap();
+        let parse_collections = || -> Vec<CollectionPosts> {
+            bloggo
+                .config
+                .collections
+                .iter()
+                .map(|collection| CollectionPosts {
+                    collection,
+                    posts: bloggo.parse_collection(collection).unwrap().0,
+                })
+                .collect()
+        };
+
+        let result = bloggo.check_output_paths(&parse_collections(), &[], &[]);
+        match result {
+            Err(Error::DuplicateOutput(path, _)) => assert_eq!("about.html", path),
+            _ => panic!("expected a duplicate output, got {:?}", result),
+        }
+
+        std::fs::remove_file(&page).unwrap();
+        std::fs::write(pages_dir.join("index.md"), "---\ntitle: Home\n---\n").unwrap();
+        let result = bloggo.check_output_paths(&parse_collections(), &[], &[]);
+        match result {
+            Err(Error::DuplicateOutput(path, _)) => assert_eq!("index.html", path),
+            _ => panic!("expected a duplicate output, got {:?}", result),
+        }
+
+        std::fs::remove_file(pages_dir.join("index.md")).unwrap();
+        assert!(bloggo
+            .check_output_paths(&parse_collections(), &[], &[])
+            .is_ok());
+    }
+
+    #[test]
+    fn build_collection_dates() {
+        let (_dir, mut bloggo) = test_bloggo();
+        let src_dir = PathBuf::from(&bloggo.src_dir);
+        for dir in ["assets", "templates", "talks/2023-03-01_bundle"] {
+            fs::create_dir_all(src_dir.join(dir)).unwrap();
+        }
+        let files = [
+            ("bloggo.toml", "[collections.talks]\n"),
+            (
+                "templates/index.html.hbs",
+                "{{#each posts}}{{title}} {{formatDateTime date \"%F\"}};{{/each}}",
+            ),
+            ("templates/post.html.hbs", "{{post.title}}"),
+            ("templates/atom.xml.hbs", ""),
+            ("talks/2023-01-01_old.md", "# Old\n"),
+            ("talks/2023-02-01_new.md", "# New\n"),
+            ("talks/2023-03-01_bundle/index.md", "# Bundle\n"),
+        ];
+        for (path, source) in files {
+            std::fs::write(src_dir.join(path), source).unwrap();
+        }
+        bloggo.build().unwrap();
+
+        let index = std::fs::read_to_string(Path::new(&bloggo.dest_dir).join("talks/index.html"));
+        assert_eq!(
+            "Bundle 2023-03-01;New 2023-02-01;Old 2023-01-01;",
+            index.unwrap()
+        );
+    }
+
+    #[test]
+    fn parse_post_reading_time() {
+        let (_dir, bloggo) = test_bloggo();
+        let words = vec!["word"; 450].join(" ");
+        let path = write_test_post(&bloggo, "2023-04-05_words.md", &words);
+        let (post, _) = bloggo.parse_post(path).unwrap();
+        assert_eq!(Some(450), post["word_count"].as_i64());
+        assert_eq!(Some(3), post["reading_time"].as_i64());
+    }
 
     #[test]
     fn extract_first_paragraph_happy_path() {
@@ -735,17 +2696,387 @@ mod test {
         assert_eq!(None, extract_first_paragraph("blah <p>foo bar"));
     }
 
+    fn post_with_dates(dates: &[(&str, &str)]) -> Post {
+        let mut post = Post::new();
+        for (key, date) in dates {
+            post.insert(key.to_string(), (*date).into());
+        }
+        parse_dates(&mut post, Path::new("post.md"), Tz::UTC).unwrap();
+        post
+    }
+
+    /// Create a post with a title and a date.
+    fn post_with_title(title: &str, date: &str) -> Post {
+        let mut post = post_with_dates(&[("date", date)]);
+        post.insert("title".into(), title.into());
+        post
+    }
+
+    /// Create a tag of the given posts, all of which are its recent posts.
+    fn tag_with_posts<'a>(name: &'a str, posts: &'a Vec<&'a Post>) -> Tag<'a> {
+        let slug = text::slugify(name);
+        Tag {
+            name,
+            index_url: format!("/{}/", slug),
+            slug,
+            count: posts.len(),
+            weight: 1,
+            posts,
+            recent_posts: posts.clone(),
+        }
+    }
+
+    /// Get the titles of a list of posts.
+    fn titles(posts: &[&Post]) -> Vec<String> {
+        posts
+            .iter()
+            .filter_map(|p| p["title"].as_string())
+            .collect()
+    }
+
+    #[test]
+    fn generate_series_order() {
+        let (_dir, bloggo) = test_bloggo();
+        let part = |title: &str, date: &str, order: Option<i64>| {
+            let mut post = post_with_title(title, date);
+            post.insert("series".into(), "The Hound".into());
+            if let Some(order) = order {
+                post.insert("series_order".into(), order.into());
+            }
+            post
+        };
+        let posts = vec![
+            part("Three", "2023-03-01", None),
+            part("Two", "2023-01-01", Some(2)),
+            part("One", "2023-02-01", Some(1)),
+            part("Four", "2023-04-01", None),
+            Post::new(),
+        ];
+        let series = bloggo.generate_series(&posts).unwrap();
+        assert_eq!(1, series.len());
+        assert_eq!("/series/the-hound/", series[0].index_url);
+
+        let context = SeriesContext {
+            series: &series[0],
+            position: Some(1),
+        };
+        let json = serde_json::to_value(&context).unwrap();
+        let titles: Vec<&str> = json["parts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|p| p["title"].as_str())
+            .collect();
+        assert_eq!(vec!["One", "Two", "Three", "Four"], titles);
+        assert_eq!(2, json["position"]);
+        assert_eq!("One", json["prev"]["title"]);
+        assert_eq!("Three", json["next"]["title"]);
+    }
+
+    #[test]
+    fn generate_series_slugs() {
+        let (_dir, bloggo) = test_bloggo();
+        let part = |series: &str| {
+            let mut post = Post::new();
+            post.insert("series".into(), series.into());
+            post
+        };
+
+        let posts = [part("C++"), part("C")];
+        assert!(matches!(
+            bloggo.generate_series(&posts),
+            Err(Error::InvalidSeries(_, _))
+        ));
+        let posts = [part("++")];
+        assert!(matches!(
+            bloggo.generate_series(&posts),
+            Err(Error::InvalidSeries(_, _))
+        ));
+    }
+
+    #[test]
+    fn render_collection_neighbours() {
+        let (_dir, mut bloggo) = test_bloggo();
+        bloggo
+            .handlebars
+            .register_template_string(
+                "post",
+                "{{post.title}}:{{prev.title}}:{{next.title}}:\
+                 {{tag_navigation.clue.next.title}}:{{#each site.posts}}{{title}}{{/each}}:\
+                 {{next.text}}{{tag_navigation.clue.next.text}}{{#each site.posts}}{{text}}\
+                 {{/each}}{{#each tags}}{{#each \
+                 recent_posts}}{{text}}{{/each}}{{/each}}",
+            )
+            .unwrap();
+        let post = |title: &str, date: &str, tags: Vec<&str>| {
+            let mut post = post_with_title(title, date);
+            post.insert("path".into(), format!("{}.html", title).into());
+            post.insert("text".into(), format!("<p>{}</p>", title).into());
+            let tags: Vec<Value> = tags.into_iter().map(Value::from).collect();
+            post.insert("tags".into(), Value::Array(tags));
+            post
+        };
+        let posts = vec![
+            post("C", "2023-03-01", vec!["clue"]),
+            post("B", "2023-02-01", vec![]),
+            post("A", "2023-01-01", vec!["clue"]),
+        ];
+        let collection = Collection::new("posts");
+        let collections = vec![CollectionPosts {
+            collection: &collection,
+            posts,
+        }];
+        let clue_posts: Vec<&Post> = vec![&collections[0].posts[0], &collections[0].posts[2]];
+        let taxonomy = Taxonomy::new("tags");
+        let taxonomies = vec![TaxonomyTerms {
+            taxonomy: &taxonomy,
+            terms: vec![tag_with_posts("clue", &clue_posts)],
+        }];
+        let site = Site {
+            posts: &collections[0].posts,
+            taxonomies: &taxonomies,
+            collections: &collections,
+            series: &[],
+        };
+        bloggo.render_collection(&collections[0], &site).unwrap();
+
+        let rendered = |title: &str| {
+            std::fs::read_to_string(Path::new(&bloggo.dest_dir).join(format!("{}.html", title)))
+                .unwrap()
+        };
+        assert_eq!("A::B:C:CBA:", rendered("A"));
+        assert_eq!("B:A:C::CBA:", rendered("B"));
+        assert_eq!("C:B:::CBA:", rendered("C"));
+    }
+
+    #[test]
+    fn related_posts_ranking() {
+        let (_dir, mut bloggo) = test_bloggo();
+        bloggo.config.related_posts = 2;
+        let posts = [
+            post_with_title("Newest", "2023-04-01"),
+            post_with_title("Both", "2023-03-01"),
+            post_with_title("Clue", "2023-02-01"),
+            post_with_title("Current", "2023-01-01"),
+        ];
+        let clue: Vec<&Post> = vec![&posts[0], &posts[1], &posts[2], &posts[3]];
+        let dog: Vec<&Post> = vec![&posts[1], &posts[3]];
+        let tags = vec![tag_with_posts("clue", &clue), tag_with_posts("dog", &dog)];
+
+        assert_eq!(
+            vec!["Both", "Newest"],
+            titles(&bloggo.related_posts(&posts[3], &tags))
+        );
+        assert_eq!(
+            vec!["Current", "Newest"],
+            titles(&bloggo.related_posts(&posts[1], &tags))
+        );
+    }
+
+    #[test]
+    fn generate_terms_slugs() {
+        let (_dir, mut bloggo) = test_bloggo();
+        bloggo.base_url = String::from("https://example.com");
+        let post = Post::new();
+        let taxonomy = Taxonomy::new("categories");
+        let term_index = |names: &[&str]| -> HashMap<String, Vec<&Post>> {
+            names
+                .iter()
+                .map(|name| (name.to_string(), vec![&post]))
+                .collect()
+        };
+
+        let index = term_index(&["rust lang", "a/b"]);
+        let terms = bloggo.generate_terms(&taxonomy, &index).unwrap().terms;
+        let json = serde_json::to_value(&terms).unwrap();
+        assert_eq!("a/b", json[0]["name"]);
+        assert_eq!("a-b", json[0]["slug"]);
+        assert_eq!(
+            "https://example.com/categories/rust-lang/",
+            json[1]["index_url"]
+        );
+
+        let index = term_index(&["C++", "c"]);
+        assert!(matches!(
+            bloggo.generate_terms(&taxonomy, &index),
+            Err(Error::InvalidTag(_, _))
+        ));
+        let index = term_index(&["++"]);
+        assert!(bloggo.generate_terms(&taxonomy, &index).is_err());
+
+        let index = term_index(&["Café"]);
+        let terms = bloggo.generate_terms(&taxonomy, &index).unwrap().terms;
+        assert_eq!("café", terms[0].slug);
+        assert_eq!(
+            "https://example.com/categories/caf%C3%A9/",
+            terms[0].index_url
+        );
+
+        bloggo.config.collections.push(Collection::new("talks"));
+        let tags = bloggo.config.taxonomies[0].clone();
+        for name in ["Talks", "Series", "tags"] {
+            let index = term_index(&[name]);
+            assert!(matches!(
+                bloggo.generate_terms(&tags, &index),
+                Err(Error::InvalidTag(_, _))
+            ));
+        }
+        let index = term_index(&["Categories"]);
+        assert!(bloggo.generate_terms(&tags, &index).is_ok());
+    }
+
+    #[test]
+    fn generate_terms_weights() {
+        let (_dir, mut bloggo) = test_bloggo();
+        bloggo.config.recent_posts = 2;
+        let posts = [
+            post_with_title("Middle", "2023-02-01"),
+            post_with_title("Oldest", "2023-01-01"),
+            post_with_title("Newest", "2023-03-01"),
+        ];
+        let taxonomy = Taxonomy {
+            output_dir: String::new(),
+            ..Taxonomy::new("tags")
+        };
+        let mut index: HashMap<String, Vec<&Post>> = HashMap::new();
+        index.insert("clue".into(), posts.iter().collect());
+        index.insert("dog".into(), vec![&posts[0], &posts[1]]);
+        index.insert("moor".into(), vec![&posts[2]]);
+        let terms = bloggo.generate_terms(&taxonomy, &index).unwrap().terms;
+        let weights: Vec<usize> = terms.iter().map(|t| t.weight).collect();
+        assert_eq!(vec![10, 5, 1], weights);
+        assert_eq!(vec!["Newest", "Middle"], titles(&terms[0].recent_posts));
+        assert_eq!(vec!["Middle", "Oldest"], titles(&terms[1].recent_posts));
+        assert_eq!(vec!["Newest"], titles(&terms[2].recent_posts));
+
+        index.insert("Tags".into(), vec![&posts[0]]);
+        assert!(bloggo.generate_terms(&taxonomy, &index).is_err());
+    }
+
+    #[test]
+    fn is_published_draft() {
+        let mut draft = post_with_dates(&[("date", "2023-04-01")]);
+        draft.insert("draft".into(), true.into());
+        let mut not_draft = draft.clone();
+        not_draft.insert("draft".into(), false.into());
+
+        let bloggo = Builder::new().build();
+        assert!(!bloggo.is_published(&draft));
+        assert!(bloggo.is_published(&not_draft));
+
+        let bloggo = Builder::new().include_drafts(true).build();
+        assert!(bloggo.is_published(&draft));
+    }
+
+    #[test]
+    fn is_published_schedule() {
+        let now = "2023-04-05T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let bloggo = Builder::new().now(now).build();
+
+        assert!(bloggo.is_published(&post_with_dates(&[("date", "2023-04-05")])));
+        assert!(!bloggo.is_published(&post_with_dates(&[("date", "2023-04-06")])));
+        assert!(!bloggo.is_published(&post_with_dates(&[
+            ("date", "2023-04-01"),
+            ("publish_date", "2023-04-06")
+        ])));
+        assert!(!bloggo.is_published(&post_with_dates(&[
+            ("date", "2023-04-01"),
+            ("expiry_date", "2023-04-05")
+        ])));
+    }
+
+    #[test]
+    fn is_published_future() {
+        let now = "2023-04-05T12:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let bloggo = Builder::new().now(now).include_future(true).build();
+
+        assert!(bloggo.is_published(&post_with_dates(&[("date", "2023-04-06")])));
+        assert!(!bloggo.is_published(&post_with_dates(&[("expiry_date", "2023-04-01")])));
+    }
+
+    #[test]
+    fn parse_dates_happy_path() {
+        let mut post = Post::new();
+        post.insert("date".into(), "2023-04-05T14:00:00+02:00".into());
+        parse_dates(&mut post, Path::new("post.md"), Tz::UTC).unwrap();
+        assert_eq!(
+            DateTime::parse_from_rfc3339("2023-04-05T12:00:00Z").ok(),
+            post["date"].as_datetime()
+        );
+    }
+
+    #[test]
+    fn parse_dates_invalid_date() {
+        let mut post = Post::new();
+        post.insert("updated".into(), "yesterday".into());
+        assert!(matches!(
+            parse_dates(&mut post, Path::new("post.md"), Tz::UTC),
+            Err(Error::InvalidDate(p, _)) if p == "post.md"
+        ));
+    }
+
+    #[test]
+    fn parse_date_formats() {
+        let tz = chrono_tz::America::New_York;
+        let expected = DateTime::parse_from_rfc3339("2023-04-05T14:00:00-04:00").ok();
+        assert_eq!(expected, parse_date("2023-04-05T14:00:00-04:00", tz));
+        assert_eq!(expected, parse_date("2023-04-05T18:00:00Z", Tz::UTC));
+        assert_eq!(expected, parse_date("Wed, 5 Apr 2023 14:00:00 -0400", tz));
+        assert_eq!(expected, parse_date("2023-04-05 14:00", tz));
+        assert_eq!(expected, parse_date("2023-04-05T14:00:00", tz));
+        assert_eq!(
+            DateTime::parse_from_rfc3339("2023-04-05T00:00:00-04:00").ok(),
+            parse_date("2023-04-05", tz)
+        );
+    }
+
+    #[test]
+    fn parse_date_unparseable() {
+        assert_eq!(None, parse_date("April 5th", Tz::UTC));
+    }
+
     #[test]
     fn extract_date_from_str_happy_path() {
         assert_eq!(
-            "2023-02-26T00:00:00Z".parse::<DateTime<Utc>>().ok(),
-            extract_date_from_str("2023-02-26-blah blah blah")
+            DateTime::parse_from_rfc3339("2023-02-26T00:00:00Z").ok(),
+            extract_date_from_str("2023-02-26-blah blah blah", Tz::UTC)
+        );
+    }
+
+    #[test]
+    fn extract_date_from_str_time_zone() {
+        assert_eq!(
+            DateTime::parse_from_rfc3339("2023-02-26T00:00:00+01:00").ok(),
+            extract_date_from_str("2023-02-26-blah blah blah", chrono_tz::Europe::Paris)
         );
     }
 
     #[test]
     fn extract_date_from_str_unhappy_path() {
-        assert_eq!(None, extract_date_from_str("This is not a date."));
+        assert_eq!(None, extract_date_from_str("This is not a date.", Tz::UTC));
+    }
+
+    #[test]
+    fn parse_toml_data_happy_path() {
+        let data = "title = \"A Title\"\ntags = [\"alpha\", \"beta\"]\n";
+        if let Value::Map(map) = parse_toml_data(data).unwrap() {
+            assert_eq!(Some("A Title".to_string()), map["title"].as_string());
+            assert!(matches!(&map["tags"], Value::Array(a) if a.len() == 2));
+        } else {
+            panic!("Parsed TOML is not a map.");
+        }
+    }
+
+    #[test]
+    fn parse_json_data_happy_path() {
+        let data = "{\n  \"title\": \"A Title\",\n  \"tags\": [\"alpha\"]\n}\nThe text.\n";
+        let (value, rest) = parse_json_data(data).unwrap();
+        if let Value::Map(map) = value {
+            assert_eq!(Some("A Title".to_string()), map["title"].as_string());
+        } else {
+            panic!("Parsed JSON is not a map.");
+        }
+        assert_eq!("\nThe text.\n", rest);
     }
 
     #[test]
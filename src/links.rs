@@ -0,0 +1,296 @@
+//! Checking the links between the pages of a built site.
+//!
+//! The `href` and `src` attributes of the generated HTML files are checked
+//! against the files in the destination directory. Links to other sites are
+//! not checked.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Component, Path, PathBuf},
+};
+
+/// A link within the site that does not resolve to a file in the destination
+/// directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// The source post containing the link or, if the link could not be
+    /// found in a source post, the generated HTML file.
+    pub file: PathBuf,
+
+    /// The line of the file that contains the link.
+    pub line: usize,
+
+    /// The destination of the link.
+    pub link: String,
+}
+
+impl Display for BrokenLink {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: broken link {}",
+            self.file.display(),
+            self.line,
+            self.link
+        )
+    }
+}
+
+/// Find the values of the `href` and `src` attributes in HTML, along with the
+/// line on which each appears.
+pub(crate) fn extract_links(html: &str) -> Vec<(usize, &str)> {
+    let mut links = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = html[pos..].find('<') {
+        let start = pos + found;
+        let end = tag_end(&html[start..]).map_or(html.len(), |e| start + e);
+        let tag = &html[start..end];
+        pos = end;
+        if tag.starts_with("<!") || tag.starts_with("</") || tag.starts_with("<?") {
+            continue;
+        }
+        for (offset, name, value) in attributes(tag) {
+            if name.eq_ignore_ascii_case("href") || name.eq_ignore_ascii_case("src") {
+                let line = html[..start + offset].matches('\n').count() + 1;
+                links.push((line, value));
+            }
+        }
+    }
+    links
+}
+
+/// Find the end of the tag at the start of a [str], skipping over quoted
+/// attribute values.
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse the attributes of an HTML tag, returning the offset of each in the
+/// tag along with its name and value.
+fn attributes(tag: &str) -> Vec<(usize, &str, &str)> {
+    let is_name_end = |c: char| c.is_whitespace() || c == '=' || c == '>' || c == '/';
+    let mut attributes = Vec::new();
+    let body = tag.trim_start_matches('<');
+    let mut pos = tag.len() - body.len() + body.find(is_name_end).unwrap_or(body.len());
+    loop {
+        let rest = &tag[pos..];
+        let trimmed = rest.trim_start_matches(|c: char| c.is_whitespace() || c == '/');
+        pos += rest.len() - trimmed.len();
+        let name_len = trimmed.find(is_name_end).unwrap_or(trimmed.len());
+        if name_len == 0 {
+            break;
+        }
+        let start = pos;
+        let name = &tag[pos..pos + name_len];
+        pos += name_len;
+        let rest = tag[pos..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        pos = tag.len() - rest.len();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let len = rest[1..].find(quote).unwrap_or(rest.len() - 1);
+                pos += len + 2;
+                &rest[1..1 + len]
+            }
+            _ => {
+                let len = rest
+                    .find(|c: char| c.is_whitespace() || c == '>')
+                    .unwrap_or(rest.len());
+                pos += len;
+                &rest[..len]
+            }
+        };
+        attributes.push((start, name, value));
+        pos = pos.min(tag.len());
+    }
+    attributes
+}
+
+/// Get the path of the file that a link on a page refers to, relative to the
+/// destination directory, or [None] if the link does not refer to a file in
+/// the site. The page is given relative to the destination directory, and
+/// links that begin with `base_url` are treated as links within the site.
+/// Percent-encoded characters in the link are decoded.
+pub(crate) fn link_target(link: &str, page: &Path, base_url: &str) -> Option<PathBuf> {
+    let link = link.split(['#', '?']).next().unwrap_or("");
+    if link.is_empty() {
+        return None;
+    }
+    let link = percent_decode(link);
+    let link = link.as_str();
+
+    let base_url = base_url.trim_end_matches('/');
+    let base_path = base_url
+        .split_once("://")
+        .map(|(_, rest)| rest.find('/').map_or("", |i| &rest[i..]))
+        .unwrap_or(base_url);
+
+    let site_path = link
+        .strip_prefix(base_url)
+        .filter(|rest| !base_url.is_empty() && (rest.is_empty() || rest.starts_with('/')));
+
+    let (mut target, relative) = if let Some(rest) = site_path {
+        (PathBuf::new(), rest)
+    } else if link.starts_with("//") || has_scheme(link) {
+        return None;
+    } else if link.starts_with('/') {
+        let rest = link.strip_prefix(base_path)?;
+        if !(base_path.is_empty() || rest.is_empty() || rest.starts_with('/')) {
+            return None;
+        }
+        (PathBuf::new(), rest)
+    } else {
+        (
+            page.parent().map(Path::to_path_buf).unwrap_or_default(),
+            link,
+        )
+    };
+
+    for component in Path::new(relative).components() {
+        match component {
+            Component::ParentDir => {
+                target.pop();
+            }
+            Component::Normal(name) => target.push(name),
+            _ => {}
+        }
+    }
+    Some(target)
+}
+
+/// Decode the percent-encoded bytes in the path of a link, such as `%20` for
+/// a space. Invalid UTF-8 sequences are replaced, and a `%` that is not
+/// followed by two hexadecimal digits is left as it is.
+pub(crate) fn percent_decode(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .filter(|h| h.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Percent-encode the bytes in a segment of a path that are not allowed
+/// unencoded in a URL, such as the `é` in `café`, so that the segment can be
+/// used in a link.
+pub(crate) fn percent_encode(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Determine whether a link begins with a URL scheme such as `https:` or
+/// `mailto:`.
+fn has_scheme(link: &str) -> bool {
+    link.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extract_links_happy_path() {
+        let html = "<a class=x href=\"/a.html\">A</a>\n\
+                    <img alt='b > c' src='b.png'>\n\
+                    <!-- <a href=\"/comment\"> -->\n\
+                    <p data-src=\"no\" title=\"href=no\">C</p><link href=c.css>";
+        assert_eq!(
+            vec![(1, "/a.html"), (2, "b.png"), (4, "c.css")],
+            extract_links(html)
+        );
+    }
+
+    #[test]
+    fn percent_decode_happy_path() {
+        assert_eq!("my post.md", percent_decode("my%20post.md"));
+        assert_eq!("café", percent_decode("caf%C3%A9"));
+        assert_eq!("100%.md", percent_decode("100%.md"));
+        assert_eq!("%zz%+1", percent_decode("%zz%+1"));
+    }
+
+    #[test]
+    fn percent_encode_happy_path() {
+        assert_eq!("rust-lang", percent_encode("rust-lang"));
+        assert_eq!("caf%C3%A9", percent_encode("café"));
+        assert_eq!("a%20b%2Fc", percent_encode("a b/c"));
+        assert_eq!("café", percent_decode(&percent_encode("café")));
+    }
+
+    #[test]
+    fn link_target_relative() {
+        let page = Path::new("2023/post.html");
+        assert_eq!(
+            Some(PathBuf::from("2023/photo.jpg")),
+            link_target("photo.jpg", page, "")
+        );
+        assert_eq!(
+            Some(PathBuf::from("index.css")),
+            link_target("../index.css?v=2", page, "")
+        );
+        assert_eq!(None, link_target("#section", page, ""));
+        assert_eq!(
+            Some(PathBuf::from("2023/a b.png")),
+            link_target("a%20b.png", page, "")
+        );
+    }
+
+    #[test]
+    fn link_target_base_url() {
+        let page = Path::new("post.html");
+        let base_url = "https://example.com/blog";
+        assert_eq!(
+            Some(PathBuf::from("holmes")),
+            link_target("https://example.com/blog/holmes/", page, base_url)
+        );
+        assert_eq!(
+            Some(PathBuf::from("index.css")),
+            link_target("/blog/index.css", page, base_url)
+        );
+        assert_eq!(None, link_target("/other/index.css", page, base_url));
+        assert_eq!(
+            None,
+            link_target("https://example.com/blogger", page, base_url)
+        );
+        assert_eq!(None, link_target("mailto:holmes@example.com", page, ""));
+        assert_eq!(None, link_target("//cdn.example.com/x.js", page, ""));
+    }
+}
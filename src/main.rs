@@ -15,13 +15,29 @@ fn main() -> ExitCode {
         ])
         .subcommand_required(true)
         .subcommand(command!("clean").about("Clean destination directory"))
-        .subcommand(command!("build").about("Build static site pages"))
+        .subcommand(
+            command!("build")
+                .about("Build static site pages")
+                .arg(arg!(--drafts "Include posts marked as drafts"))
+                .arg(arg!(--future "Include posts with a publish date in the future"))
+                .arg(arg!(--"check-links" "Check the links in the built site")),
+        )
+        .subcommand(command!("check-links").about("Check the links in the built site"))
         .get_matches();
 
     let src_dir = arg_or_env_or_default(matches.get_one("source"), "BLOGGO_SRC", "source");
     let dest_dir = arg_or_env_or_default(matches.get_one("dest"), "BLOGGO_DEST", "dest");
     let base_url = arg_or_env_or_default(matches.get_one("base"), "BLOGGO_BASE", "");
     let verbose = matches.get_flag("verbose");
+    let build_flag = |flag| {
+        matches
+            .subcommand_matches("build")
+            .map(|m| m.get_flag(flag))
+            .unwrap_or(false)
+    };
+    let include_drafts = build_flag("drafts");
+    let include_future = build_flag("future");
+    let link_check = build_flag("check-links");
 
     init_logger(verbose);
 
@@ -29,11 +45,15 @@ fn main() -> ExitCode {
         .src_dir(src_dir)
         .dest_dir(dest_dir)
         .base_url(base_url)
+        .include_drafts(include_drafts)
+        .include_future(include_future)
+        .with_link_check(link_check)
         .build();
 
     let result = match matches.subcommand() {
         Some(("clean", _)) => b.clean(),
         Some(("build", _)) => b.build(),
+        Some(("check-links", _)) => b.check_links(),
         _ => panic!("This should never happen."),
     };
 
@@ -0,0 +1,430 @@
+//! Rendering of Markdown post content to HTML.
+
+use crate::{highlight::highlight_code_blocks, text::slugify, value::Value};
+use pulldown_cmark::{html, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use std::collections::{HashMap, HashSet};
+
+/// The result of rendering Markdown source to HTML.
+pub(crate) struct Markdown {
+    /// The rendered HTML.
+    pub html: String,
+
+    /// The text of the first level one heading, if it was removed from the
+    /// HTML to be used as the title.
+    pub title: Option<String>,
+
+    /// The table of contents, as a tree of headings.
+    pub toc: Vec<Value>,
+
+    /// The destinations of relative links to Markdown files that could not
+    /// be resolved.
+    pub broken_links: Vec<String>,
+}
+
+/// The Markdown extensions that can be enabled or disabled by name.
+const EXTENSIONS: [(&str, Options); 6] = [
+    ("tables", Options::ENABLE_TABLES),
+    ("footnotes", Options::ENABLE_OLD_FOOTNOTES),
+    ("strikethrough", Options::ENABLE_STRIKETHROUGH),
+    ("tasklists", Options::ENABLE_TASKLISTS),
+    ("heading_attributes", Options::ENABLE_HEADING_ATTRIBUTES),
+    ("smart_punctuation", Options::ENABLE_SMART_PUNCTUATION),
+];
+
+/// Get the Markdown extensions that are enabled by default, which are all of
+/// them except smart punctuation.
+pub(crate) fn default_options() -> Options {
+    let mut options = Options::all();
+    options.remove(Options::ENABLE_SMART_PUNCTUATION);
+    options
+}
+
+/// Enable or disable the Markdown extensions named in a map of booleans, such
+/// as `{ smart_punctuation = true, tables = false }`.
+pub(crate) fn apply_extensions(
+    options: &mut Options,
+    extensions: &Value,
+) -> std::result::Result<(), String> {
+    let map = match extensions {
+        Value::Map(map) => map,
+        _ => return Err(String::from("not a map")),
+    };
+    for (name, enabled) in map {
+        let flag = EXTENSIONS
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, flag)| *flag)
+            .ok_or_else(|| format!("unknown extension {}", name))?;
+        match enabled.as_bool() {
+            Some(true) => options.insert(flag),
+            Some(false) => options.remove(flag),
+            None => return Err(format!("{}: not a boolean", name)),
+        }
+    }
+    Ok(())
+}
+
+/// Render Markdown source to HTML with the given extensions. If `infer_title`
+/// is true, the first level one heading is removed from the HTML and returned
+/// as the title. The relative destinations of links and images are replaced
+/// with the URLs returned by `resolve_link`, which returns [None] if the
+/// destination does not exist.
+pub(crate) fn render_markdown<F>(
+    source: &str,
+    options: Options,
+    infer_title: bool,
+    resolve_link: F,
+) -> Markdown
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let parser = Parser::new_ext(source, options);
+    let (title, events) = if infer_title {
+        extract_title(parser)
+    } else {
+        (None, parser.collect())
+    };
+    let (events, broken_links) = rewrite_links(events, resolve_link);
+    let (events, headings) = add_heading_ids(events);
+    let events = highlight_code_blocks(events);
+
+    let mut text = String::with_capacity(source.len());
+    html::push_html(&mut text, events.into_iter());
+    Markdown {
+        html: text,
+        title,
+        toc: nest_headings(headings),
+        broken_links,
+    }
+}
+
+/// Determine whether a link destination is relative to the post, such as
+/// `2023-03-01_other.md` or `../images/photo.jpg`.
+fn is_relative_link(dest_url: &str) -> bool {
+    let path = dest_url.split(['#', '?']).next().unwrap_or("");
+    let has_scheme = path
+        .split_once(':')
+        .is_some_and(|(scheme, _)| !scheme.contains('/'));
+    !has_scheme && !path.is_empty() && !path.starts_with('/')
+}
+
+/// Replace the relative destinations of links and images with the URLs
+/// returned by `resolve_link`, which is given the path of each destination
+/// without its query or fragment, keeping the query and fragment. Returns the
+/// events along with the destinations that could not be resolved, which are
+/// left unchanged.
+fn rewrite_links<'e, F>(
+    events: Vec<Event<'e>>,
+    mut resolve_link: F,
+) -> (Vec<Event<'e>>, Vec<String>)
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut broken_links = Vec::new();
+    let mut rewrite = |dest_url: CowStr<'e>| -> CowStr<'e> {
+        if !is_relative_link(&dest_url) {
+            return dest_url;
+        }
+        let path_end = dest_url.find(['?', '#']).unwrap_or(dest_url.len());
+        let (path, suffix) = dest_url.split_at(path_end);
+        match resolve_link(path) {
+            Some(url) => CowStr::from(format!("{}{}", url, suffix)),
+            None => {
+                broken_links.push(dest_url.to_string());
+                dest_url
+            }
+        }
+    };
+    let events = events
+        .into_iter()
+        .map(|event| match event {
+            Event::Start(Tag::Link {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Link {
+                link_type,
+                dest_url: rewrite(dest_url),
+                title,
+                id,
+            }),
+            Event::Start(Tag::Image {
+                link_type,
+                dest_url,
+                title,
+                id,
+            }) => Event::Start(Tag::Image {
+                link_type,
+                dest_url: rewrite(dest_url),
+                title,
+                id,
+            }),
+            _ => event,
+        })
+        .collect();
+    (events, broken_links)
+}
+
+/// Remove the first level one heading from a stream of Markdown events,
+/// returning its text along with the remaining events.
+fn extract_title<'e, I>(events: I) -> (Option<String>, Vec<Event<'e>>)
+where
+    I: Iterator<Item = Event<'e>>,
+{
+    let mut title: Option<String> = None;
+    let mut in_title = false;
+    let mut remaining = Vec::new();
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading {
+                level: HeadingLevel::H1,
+                ..
+            }) if title.is_none() => {
+                in_title = true;
+                title = Some(String::new());
+            }
+            Event::End(TagEnd::Heading(HeadingLevel::H1)) if in_title => {
+                in_title = false;
+            }
+            Event::Text(ref t) | Event::Code(ref t) if in_title => {
+                if let Some(s) = title.as_mut() {
+                    s.push_str(t);
+                }
+            }
+            _ if in_title => {}
+            _ => remaining.push(event),
+        }
+    }
+    (title, remaining)
+}
+
+/// A heading in the table of contents of a post.
+struct Heading {
+    level: usize,
+    text: String,
+    id: String,
+    children: Vec<Heading>,
+}
+
+impl From<Heading> for Value {
+    fn from(heading: Heading) -> Value {
+        let mut map = HashMap::new();
+        map.insert("level".into(), (heading.level as i64).into());
+        map.insert("text".into(), heading.text.into());
+        map.insert("id".into(), heading.id.into());
+        let children: Vec<Value> = heading.children.into_iter().map(|h| h.into()).collect();
+        map.insert("children".into(), children.into());
+        Value::Map(map)
+    }
+}
+
+/// Give every heading in a list of Markdown events a unique `id` attribute,
+/// derived from the text of the heading unless one was given explicitly.
+/// Returns the events along with the headings in the order they appear.
+fn add_heading_ids(events: Vec<Event>) -> (Vec<Event>, Vec<Heading>) {
+    let mut used_ids: HashSet<String> = events
+        .iter()
+        .filter_map(|e| match e {
+            Event::Start(Tag::Heading { id: Some(id), .. }) => Some(id.to_string()),
+            _ => None,
+        })
+        .collect();
+    let mut headings = Vec::new();
+    let mut result = Vec::with_capacity(events.len());
+    let mut heading_start: Option<usize> = None;
+    let mut heading_text = String::new();
+
+    for event in events {
+        match event {
+            Event::Start(Tag::Heading { .. }) => {
+                heading_start = Some(result.len());
+                heading_text.clear();
+                result.push(event);
+            }
+            Event::End(TagEnd::Heading(level)) => {
+                if let Some(Event::Start(Tag::Heading { id, .. })) =
+                    heading_start.take().map(|i| &mut result[i])
+                {
+                    let heading_id = match id {
+                        Some(existing) => existing.to_string(),
+                        None => {
+                            let unique = unique_id(&slugify(&heading_text), &mut used_ids);
+                            *id = Some(CowStr::from(unique.clone()));
+                            unique
+                        }
+                    };
+                    headings.push(Heading {
+                        level: level as usize,
+                        text: heading_text.clone(),
+                        id: heading_id,
+                        children: Vec::new(),
+                    });
+                }
+                result.push(event);
+            }
+            Event::Text(ref t) | Event::Code(ref t) if heading_start.is_some() => {
+                heading_text.push_str(t);
+                result.push(event);
+            }
+            _ => result.push(event),
+        }
+    }
+    (result, headings)
+}
+
+/// Make an id unique by appending a number to it if it is already in use,
+/// and record it as used.
+fn unique_id(id: &str, used_ids: &mut HashSet<String>) -> String {
+    let base = if id.is_empty() { "section" } else { id };
+    let mut unique = String::from(base);
+    let mut n = 0;
+    while used_ids.contains(&unique) {
+        n += 1;
+        unique = format!("{}-{}", base, n);
+    }
+    used_ids.insert(unique.clone());
+    unique
+}
+
+/// Arrange a flat list of headings into a tree, where each heading contains
+/// the following headings of a lower level as its children.
+fn nest_headings(headings: Vec<Heading>) -> Vec<Value> {
+    let mut roots: Vec<Heading> = Vec::new();
+    let mut open: Vec<Heading> = Vec::new();
+
+    fn close(heading: Heading, open: &mut [Heading], roots: &mut Vec<Heading>) {
+        match open.last_mut() {
+            Some(parent) => parent.children.push(heading),
+            None => roots.push(heading),
+        }
+    }
+
+    for heading in headings {
+        while open.last().is_some_and(|h| h.level >= heading.level) {
+            if let Some(done) = open.pop() {
+                close(done, &mut open, &mut roots);
+            }
+        }
+        open.push(heading);
+    }
+    while let Some(done) = open.pop() {
+        close(done, &mut open, &mut roots);
+    }
+    roots.into_iter().map(|h| h.into()).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn extract_title_happy_path() {
+        let parser = Parser::new("Intro.\n\n# The `Title`\n\nBody text.\n");
+        let (title, events) = extract_title(parser);
+        let mut text = String::new();
+        html::push_html(&mut text, events.into_iter());
+        assert_eq!(Some("The Title".to_string()), title);
+        assert_eq!("<p>Intro.</p>\n<p>Body text.</p>\n", text);
+    }
+
+    #[test]
+    fn extract_title_not_there() {
+        let parser = Parser::new("## Subheading\n\nBody text.\n");
+        let (title, events) = extract_title(parser);
+        let mut text = String::new();
+        html::push_html(&mut text, events.into_iter());
+        assert_eq!(None, title);
+        assert_eq!("<h2>Subheading</h2>\n<p>Body text.</p>\n", text);
+    }
+
+    #[test]
+    fn apply_extensions_happy_path() {
+        let mut extensions = HashMap::new();
+        extensions.insert("smart_punctuation".to_string(), true.into());
+        extensions.insert("strikethrough".to_string(), false.into());
+        let mut options = default_options();
+        apply_extensions(&mut options, &Value::Map(extensions)).unwrap();
+
+        let markdown = render_markdown("\"Quoted\" ~~struck~~\n", options, false, |_| None);
+        assert_eq!("<p>“Quoted” ~~struck~~</p>\n", markdown.html);
+    }
+
+    #[test]
+    fn apply_extensions_unknown_extension() {
+        let mut extensions = HashMap::new();
+        extensions.insert("emoji".to_string(), true.into());
+        let mut options = default_options();
+        assert_eq!(
+            Err("unknown extension emoji".to_string()),
+            apply_extensions(&mut options, &Value::Map(extensions))
+        );
+    }
+
+    #[test]
+    fn render_markdown_heading_ids() {
+        let markdown = render_markdown(
+            "## Intro\n\n## The *Case*\n\n## Intro\n\n## Custom {#intro-1}\n",
+            default_options(),
+            false,
+            |_| None,
+        );
+        assert_eq!(
+            "<h2 id=\"intro\">Intro</h2>\n\
+             <h2 id=\"the-case\">The <em>Case</em></h2>\n\
+             <h2 id=\"intro-2\">Intro</h2>\n\
+             <h2 id=\"intro-1\">Custom</h2>\n",
+            markdown.html
+        );
+    }
+
+    #[test]
+    fn render_markdown_toc() {
+        let markdown = render_markdown(
+            "# One\n\n## Two\n\n### Three\n\n## Four\n\n# Five\n",
+            default_options(),
+            false,
+            |_| None,
+        );
+        let ids = |toc: &[Value]| -> Vec<String> {
+            toc.iter()
+                .filter_map(|v| match v {
+                    Value::Map(m) => m["id"].as_string(),
+                    _ => None,
+                })
+                .collect()
+        };
+        assert_eq!(vec!["one", "five"], ids(&markdown.toc));
+        if let Value::Map(one) = &markdown.toc[0] {
+            if let Value::Array(children) = &one["children"] {
+                assert_eq!(vec!["two", "four"], ids(children));
+                return;
+            }
+        }
+        panic!("Table of contents is not nested.");
+    }
+
+    #[test]
+    fn render_markdown_rewrite_links() {
+        let markdown = render_markdown(
+            "[a](2023-03-01_other.md#clue) [b](missing.md) [c](https://example.com/x.md) \
+             ![d](photo.jpg?v=2) [e](#top)\n",
+            default_options(),
+            false,
+            |path| match path {
+                "2023-03-01_other.md" => Some("/2023-03-01_other.html".to_string()),
+                "photo.jpg" => Some("/trip/photo.jpg".to_string()),
+                _ => None,
+            },
+        );
+        assert_eq!(
+            "<p><a href=\"/2023-03-01_other.html#clue\">a</a> \
+             <a href=\"missing.md\">b</a> \
+             <a href=\"https://example.com/x.md\">c</a> \
+             <img src=\"/trip/photo.jpg?v=2\" alt=\"d\" /> \
+             <a href=\"#top\">e</a></p>\n",
+            markdown.html
+        );
+        assert_eq!(vec!["missing.md"], markdown.broken_links);
+    }
+}
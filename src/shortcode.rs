@@ -0,0 +1,281 @@
+//! Shortcodes, which embed the output of Handlebars templates in a post.
+//!
+//! A shortcode such as `{{< figure src="holmes.jpg" caption="Holmes" >}}`
+//! renders the `shortcodes/figure.html.hbs` template, with the arguments of
+//! the shortcode as the properties passed to the template. A shortcode can
+//! also enclose content, as in `{{< callout >}}Note this.{{< /callout >}}`,
+//! which is passed to the template as the `inner` property. A shortcode can
+//! be written literally by enclosing it in comments: `{{</* figure */>}}`.
+
+use handlebars::Handlebars;
+use std::collections::HashMap;
+
+/// The directory in the templates directory containing shortcode templates.
+const TEMPLATE_DIR: &str = "shortcodes";
+
+/// An error in a shortcode, with the byte offset of the shortcode in the
+/// source.
+pub(crate) struct ShortcodeError {
+    pub offset: usize,
+    pub message: String,
+}
+
+/// A shortcode that appears in the source of a post.
+struct Token<'s> {
+    /// The byte offset of the start of the shortcode in the source.
+    start: usize,
+    /// The byte offset of the end of the shortcode in the source.
+    end: usize,
+    kind: TokenKind<'s>,
+}
+
+enum TokenKind<'s> {
+    Open {
+        name: &'s str,
+        args: HashMap<String, String>,
+    },
+    Close {
+        name: &'s str,
+    },
+    Literal(&'s str),
+}
+
+/// Replace the shortcodes in the source of a post with the output of their
+/// templates.
+pub(crate) fn expand_shortcodes(
+    source: &str,
+    handlebars: &Handlebars,
+) -> Result<String, ShortcodeError> {
+    let tokens = tokenize(source)?;
+    if tokens.is_empty() {
+        return Ok(String::from(source));
+    }
+    expand(source, &tokens, 0, source.len(), handlebars)
+}
+
+/// Expand the shortcodes in the section of the source between `start` and
+/// `end`, which contains the given tokens.
+fn expand(
+    source: &str,
+    tokens: &[Token],
+    start: usize,
+    end: usize,
+    handlebars: &Handlebars,
+) -> Result<String, ShortcodeError> {
+    let mut expanded = String::with_capacity(end - start);
+    let mut pos = start;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = &tokens[i];
+        expanded.push_str(&source[pos..token.start]);
+        match &token.kind {
+            TokenKind::Literal(s) => {
+                expanded.push_str("{{<");
+                expanded.push_str(s);
+                expanded.push_str(">}}");
+                pos = token.end;
+            }
+            TokenKind::Close { name } => {
+                return Err(ShortcodeError {
+                    offset: token.start,
+                    message: format!("closing shortcode {} was not opened", name),
+                });
+            }
+            TokenKind::Open { name, args } => {
+                let inner;
+                let mut context: HashMap<&str, &str> =
+                    args.iter().map(|(k, v)| (k.as_str(), v.as_str())).collect();
+                match find_close(&tokens[i + 1..], name) {
+                    Some(j) => {
+                        let close = &tokens[i + 1 + j];
+                        inner = expand(
+                            source,
+                            &tokens[i + 1..i + 1 + j],
+                            token.end,
+                            close.start,
+                            handlebars,
+                        )?;
+                        context.insert("inner", &inner);
+                        pos = close.end;
+                        i += j + 1;
+                    }
+                    None => pos = token.end,
+                }
+                let template = format!("{}/{}", TEMPLATE_DIR, name);
+                let output =
+                    handlebars
+                        .render(&template, &context)
+                        .map_err(|e| ShortcodeError {
+                            offset: token.start,
+                            message: e.to_string(),
+                        })?;
+                expanded.push_str(&output);
+            }
+        }
+        i += 1;
+    }
+    expanded.push_str(&source[pos..end]);
+    Ok(expanded)
+}
+
+/// Find the index of the token that closes a shortcode with the given name,
+/// allowing for shortcodes of the same name nested within it.
+fn find_close(tokens: &[Token], name: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.kind {
+            TokenKind::Open { name: n, .. } if n == name => depth += 1,
+            TokenKind::Close { name: n } if n == name => {
+                if depth == 0 {
+                    return Some(i);
+                }
+                depth -= 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Find all of the shortcodes in the source of a post.
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>, ShortcodeError> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = source[pos..].find("{{<") {
+        let start = pos + found;
+        let content_start = start + 3;
+        let content_end = source[content_start..]
+            .find(">}}")
+            .map(|e| content_start + e)
+            .ok_or_else(|| ShortcodeError {
+                offset: start,
+                message: String::from("shortcode is not terminated by >}}"),
+            })?;
+        let content = &source[content_start..content_end];
+        let trimmed = content.trim();
+        let literal = trimmed
+            .strip_prefix("/*")
+            .and_then(|s| s.strip_suffix("*/"));
+        let kind = if let Some(literal) = literal {
+            TokenKind::Literal(literal)
+        } else if let Some(name) = trimmed.strip_prefix('/') {
+            TokenKind::Close { name: name.trim() }
+        } else {
+            let (name, args) = parse_shortcode(trimmed).map_err(|message| ShortcodeError {
+                offset: start,
+                message,
+            })?;
+            TokenKind::Open { name, args }
+        };
+        pos = content_end + 3;
+        tokens.push(Token {
+            start,
+            end: pos,
+            kind,
+        });
+    }
+    Ok(tokens)
+}
+
+/// Parse the name and arguments of a shortcode, such as
+/// `figure src="holmes.jpg" caption='Sherlock Holmes' width=300`.
+fn parse_shortcode(content: &str) -> Result<(&str, HashMap<String, String>), String> {
+    let name_end = content.find(char::is_whitespace).unwrap_or(content.len());
+    let name = &content[..name_end];
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!("invalid shortcode name: {}", name));
+    }
+
+    let mut args = HashMap::new();
+    let mut rest = content[name_end..].trim_start();
+    while !rest.is_empty() {
+        let eq = rest
+            .find('=')
+            .ok_or_else(|| format!("expected key=value argument: {}", rest))?;
+        let key = rest[..eq].trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(format!("invalid argument name: {}", key));
+        }
+        rest = rest[eq + 1..].trim_start();
+        let value;
+        match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let close = rest[1..]
+                    .find(quote)
+                    .ok_or_else(|| format!("unterminated value for argument {}", key))?;
+                value = &rest[1..close + 1];
+                rest = &rest[close + 2..];
+            }
+            _ => {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                value = &rest[..end];
+                rest = &rest[end..];
+            }
+        }
+        args.insert(String::from(key), String::from(value));
+        rest = rest.trim_start();
+    }
+    Ok((name, args))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn handlebars() -> Handlebars<'static> {
+        let mut handlebars = Handlebars::new();
+        handlebars
+            .register_template_string(
+                "shortcodes/figure",
+                "<figure><img src=\"{{src}}\"><figcaption>{{caption}}</figcaption></figure>",
+            )
+            .unwrap();
+        handlebars
+            .register_template_string("shortcodes/callout", "<aside>{{{inner}}}</aside>")
+            .unwrap();
+        handlebars
+    }
+
+    fn expand_or_message(source: &str) -> Result<String, String> {
+        expand_shortcodes(source, &handlebars()).map_err(|e| e.message)
+    }
+
+    #[test]
+    fn expand_shortcodes_happy_path() {
+        assert_eq!(
+            Ok("A <figure><img src=\"x.jpg\"><figcaption>Holmes &amp; Watson</figcaption></figure> B".to_string()),
+            expand_or_message("A {{< figure src=\"x.jpg\" caption='Holmes & Watson' >}} B")
+        );
+    }
+
+    #[test]
+    fn expand_shortcodes_inner() {
+        assert_eq!(
+            Ok("<aside>Note <aside>this</aside>.</aside>".to_string()),
+            expand_or_message(
+                "{{< callout >}}Note {{< callout >}}this{{< /callout >}}.{{< /callout >}}"
+            )
+        );
+    }
+
+    #[test]
+    fn expand_shortcodes_literal() {
+        assert_eq!(
+            Ok("Use {{< figure >}} for figures.".to_string()),
+            expand_or_message("Use {{</* figure */>}} for figures.")
+        );
+    }
+
+    #[test]
+    fn expand_shortcodes_errors() {
+        let error = expand_shortcodes("Line one\n{{< nonexistent >}}", &handlebars()).err();
+        assert_eq!(Some(9), error.map(|e| e.offset));
+        assert!(expand_or_message("{{< figure src=\"x.jpg >}}").is_err());
+        assert!(expand_or_message("{{< figure ").is_err());
+        assert!(expand_or_message("{{< /callout >}}").is_err());
+    }
+}
@@ -0,0 +1,282 @@
+//! Utilities for converting rendered HTML into plain text, and for shortening
+//! text and HTML to a number of words.
+
+/// HTML elements that never have a closing tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// HTML elements whose text is separated from the text around them.
+const BLOCK_ELEMENTS: [&str; 21] = [
+    "blockquote",
+    "br",
+    "dd",
+    "div",
+    "dl",
+    "dt",
+    "figcaption",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "td",
+    "th",
+    "ul",
+];
+
+/// Remove the tags from HTML and decode its character references, collapsing
+/// whitespace into single spaces.
+///
+/// # Example
+///
+/// ```
+/// use bloggo::text::html_to_text;
+///
+/// assert_eq!(
+///     "Holmes & Watson",
+///     html_to_text("<p><em>Holmes</em> &amp;\n  Watson</p>")
+/// );
+/// ```
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            rest = match rest.find('>') {
+                Some(end) => {
+                    if BLOCK_ELEMENTS.contains(&tag_name(&rest[..=end]).as_str()) {
+                        text.push(' ');
+                    }
+                    &rest[end + 1..]
+                }
+                None => "",
+            };
+        } else if c == '&' {
+            let (decoded, len) = decode_reference(rest);
+            text.push_str(&decoded);
+            rest = &rest[len..];
+        } else {
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Decode the character reference at the start of a [str], returning the
+/// decoded text and the number of bytes consumed. Unknown references are
+/// returned as-is.
+fn decode_reference(s: &str) -> (String, usize) {
+    let end = match s.find(';') {
+        Some(end) if end <= 10 => end,
+        _ => return (String::from("&"), 1),
+    };
+    let name = &s[1..end];
+    let decoded = match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => name
+            .strip_prefix("#x")
+            .or_else(|| name.strip_prefix("#X"))
+            .map(|hex| u32::from_str_radix(hex, 16))
+            .or_else(|| name.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+            .and_then(|r| r.ok())
+            .and_then(char::from_u32),
+    };
+    match decoded {
+        Some(c) => (String::from(c), end + 1),
+        None => (String::from("&"), 1),
+    }
+}
+
+/// Shorten text to at most the given number of words, breaking at a word
+/// boundary and appending an ellipsis if any words were removed.
+///
+/// # Example
+///
+/// ```
+/// use bloggo::text::truncate_words;
+///
+/// assert_eq!("one two…", truncate_words("one two three", 2));
+/// assert_eq!("one two three", truncate_words("one two three", 3));
+/// ```
+pub fn truncate_words(text: &str, words: usize) -> String {
+    let mut split = text.split_whitespace();
+    let mut truncated = split.by_ref().take(words).collect::<Vec<&str>>().join(" ");
+    if split.next().is_some() {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// Shorten HTML to at most the given number of words of text, appending an
+/// ellipsis if any words were removed and closing any elements that were
+/// left open.
+///
+/// # Example
+///
+/// ```
+/// use bloggo::text::truncate_html;
+///
+/// assert_eq!(
+///     "<p>one <em>two…</em></p>",
+///     truncate_html("<p>one <em>two three</em> four</p>", 2)
+/// );
+/// ```
+pub fn truncate_html(html: &str, words: usize) -> String {
+    let mut truncated = String::with_capacity(html.len());
+    let mut open_elements: Vec<String> = Vec::new();
+    let mut count = 0_usize;
+    let mut in_word = false;
+    let mut rest = html;
+
+    while let Some(c) = rest.chars().next() {
+        if c == '<' {
+            let end = match rest.find('>') {
+                Some(end) => end,
+                None => break,
+            };
+            let tag = &rest[..=end];
+            let name = tag_name(tag);
+            if tag.starts_with("</") {
+                if let Some(i) = open_elements.iter().rposition(|e| *e == name) {
+                    open_elements.truncate(i);
+                }
+            } else if !name.is_empty()
+                && !tag.ends_with("/>")
+                && !VOID_ELEMENTS.contains(&name.as_str())
+            {
+                open_elements.push(name);
+            }
+            truncated.push_str(tag);
+            in_word = false;
+            rest = &rest[end + 1..];
+        } else {
+            if c.is_whitespace() {
+                in_word = false;
+            } else if !in_word {
+                if count == words {
+                    let trimmed = truncated.trim_end().len();
+                    truncated.truncate(trimmed);
+                    truncated.push('…');
+                    break;
+                }
+                in_word = true;
+                count += 1;
+            }
+            truncated.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    for name in open_elements.iter().rev() {
+        truncated.push_str("</");
+        truncated.push_str(name);
+        truncated.push('>');
+    }
+    truncated
+}
+
+/// Convert text into a slug that is safe to use in URLs, paths and HTML ids:
+/// letters and numbers are lowercased, runs of other characters are replaced
+/// by a single hyphen, and leading and trailing hyphens are removed.
+///
+/// # Example
+///
+/// ```
+/// use bloggo::text::slugify;
+///
+/// assert_eq!("the-red-headed-league", slugify("The Red-Headed League!"));
+/// ```
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut hyphen = false;
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            if hyphen && !slug.is_empty() {
+                slug.push('-');
+            }
+            hyphen = false;
+            slug.extend(c.to_lowercase());
+        } else {
+            hyphen = true;
+        }
+    }
+    slug
+}
+
+/// Get the lowercase element name of an HTML tag such as `<p class="x">` or
+/// `</p>`. Comments and declarations have an empty name.
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn html_to_text_numeric_references() {
+        assert_eq!(
+            "It’s “quoted”",
+            html_to_text("It&#8217;s &#x201C;quoted&#x201d;")
+        );
+    }
+
+    #[test]
+    fn html_to_text_inline_and_block_elements() {
+        assert_eq!(
+            "Holmes said so.",
+            html_to_text("<p>Hol<b>mes</b></p><p>said so.</p>")
+        );
+    }
+
+    #[test]
+    fn html_to_text_unknown_reference() {
+        assert_eq!("AT&T &bogus; x", html_to_text("AT&T &bogus; x"));
+    }
+
+    #[test]
+    fn slugify_non_ascii() {
+        assert_eq!("café-crème", slugify("  Café   Crème "));
+        assert_eq!("", slugify("!?"));
+    }
+
+    #[test]
+    fn truncate_words_short_text() {
+        assert_eq!("", truncate_words("", 5));
+    }
+
+    #[test]
+    fn truncate_html_void_elements() {
+        assert_eq!(
+            "<p>one<br/> two <img src=\"x.jpg\">…</p>",
+            truncate_html(
+                "<p>one<br/> two <img src=\"x.jpg\"> three</p><p>four</p>",
+                2
+            )
+        );
+    }
+
+    #[test]
+    fn truncate_html_not_truncated() {
+        assert_eq!("<p>one two</p>", truncate_html("<p>one two</p>", 2));
+    }
+}
@@ -2,6 +2,7 @@
 //! trait implementations.
 
 use crate::{error::Error, Result};
+use chrono::{DateTime, FixedOffset, SecondsFormat};
 use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
 use std::collections::HashMap;
 
@@ -20,6 +21,7 @@ pub enum Value {
     Boolean(bool),
     Number(Number),
     String(String),
+    DateTime(DateTime<FixedOffset>),
     Array(Vec<Value>),
     Map(HashMap<String, Value>),
 }
@@ -44,6 +46,92 @@ impl Value {
             _ => None,
         }
     }
+
+    /// Return [Some]([bool]) if the Value is a boolean, [None] otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bloggo::value::Value;
+    ///
+    /// let boolean = Value::Boolean(true);
+    /// let string  = Value::String("true".to_string());
+    ///
+    /// assert_eq!(Some(true), boolean.as_bool());
+    /// assert_eq!(None, string.as_bool());
+    /// ```
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Boolean(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Return [Some]([i64]) if the Value is an integer, [None] otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bloggo::value::Value;
+    ///
+    /// let integer = Value::from(42);
+    /// let float   = Value::from(4.2);
+    ///
+    /// assert_eq!(Some(42), integer.as_i64());
+    /// assert_eq!(None, float.as_i64());
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::Number(Number::Integer(i)) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// Return [Some]([f64]) if the Value is a number, [None] otherwise.
+    /// Integers are converted to floats.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bloggo::value::Value;
+    ///
+    /// let integer = Value::from(42);
+    /// let float   = Value::from(4.2);
+    ///
+    /// assert_eq!(Some(42.0), integer.as_f64());
+    /// assert_eq!(Some(4.2), float.as_f64());
+    /// assert_eq!(None, Value::from("42").as_f64());
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Number(Number::Integer(i)) => Some(*i as f64),
+            Value::Number(Number::Float(f)) => Some(*f),
+            _ => None,
+        }
+    }
+
+    /// Return [Some]([DateTime]) if the Value is a date and time, [None]
+    /// otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bloggo::value::Value;
+    /// use chrono::DateTime;
+    ///
+    /// let dt = DateTime::parse_from_rfc3339("2023-04-05T12:00:00Z").unwrap();
+    /// let datetime = Value::DateTime(dt);
+    /// let string = Value::String("2023-04-05T12:00:00Z".to_string());
+    ///
+    /// assert_eq!(Some(dt), datetime.as_datetime());
+    /// assert_eq!(None, string.as_datetime());
+    /// ```
+    pub fn as_datetime(&self) -> Option<DateTime<FixedOffset>> {
+        match self {
+            Value::DateTime(dt) => Some(*dt),
+            _ => None,
+        }
+    }
 }
 
 impl From<String> for Value {
@@ -76,6 +164,12 @@ impl From<f64> for Value {
     }
 }
 
+impl From<DateTime<FixedOffset>> for Value {
+    fn from(dt: DateTime<FixedOffset>) -> Value {
+        Value::DateTime(dt)
+    }
+}
+
 impl From<Vec<Value>> for Value {
     fn from(v: Vec<Value>) -> Value {
         Value::Array(v)
@@ -134,6 +228,76 @@ impl TryFrom<serde_yaml::Value> for Value {
     }
 }
 
+impl TryFrom<toml::Value> for Value {
+    type Error = Error;
+
+    fn try_from(tval: toml::Value) -> Result<Value> {
+        match tval {
+            toml::Value::Boolean(b) => Ok(Value::Boolean(b)),
+            toml::Value::Integer(i) => Ok(Value::Number(Number::Integer(i))),
+            toml::Value::Float(f) => Ok(Value::Number(Number::Float(f))),
+            toml::Value::String(s) => Ok(Value::String(s)),
+            toml::Value::Datetime(dt) => Ok(Value::String(dt.to_string())),
+            toml::Value::Array(a) => {
+                let mut vec = Vec::with_capacity(a.len());
+                for tv in a {
+                    let bv = Value::try_from(tv)?;
+                    vec.push(bv);
+                }
+                Ok(Value::Array(vec))
+            }
+            toml::Value::Table(t) => {
+                let mut map = HashMap::new();
+                for (k, v) in t {
+                    let value = Value::try_from(v)?;
+                    map.insert(k, value);
+                }
+                Ok(Value::Map(map))
+            }
+        }
+    }
+}
+
+impl TryFrom<serde_json::Value> for Value {
+    type Error = Error;
+
+    fn try_from(jval: serde_json::Value) -> Result<Value> {
+        match jval {
+            serde_json::Value::Null => Ok(Value::Null),
+            serde_json::Value::Bool(b) => Ok(Value::Boolean(b)),
+            serde_json::Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    Ok(Value::Number(Number::Integer(i)))
+                } else if let Some(f) = n.as_f64() {
+                    Ok(Value::Number(Number::Float(f)))
+                } else {
+                    Err(Error::Other(format!(
+                        "Unknown number format while parsing JSON: {}",
+                        n
+                    )))
+                }
+            }
+            serde_json::Value::String(s) => Ok(Value::String(s)),
+            serde_json::Value::Array(a) => {
+                let mut vec = Vec::with_capacity(a.len());
+                for jv in a {
+                    let bv = Value::try_from(jv)?;
+                    vec.push(bv);
+                }
+                Ok(Value::Array(vec))
+            }
+            serde_json::Value::Object(o) => {
+                let mut map = HashMap::new();
+                for (k, v) in o {
+                    let value = Value::try_from(v)?;
+                    map.insert(k, value);
+                }
+                Ok(Value::Map(map))
+            }
+        }
+    }
+}
+
 impl Serialize for Value {
     fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
     where
@@ -141,6 +305,9 @@ impl Serialize for Value {
     {
         match self {
             Value::String(s) => serializer.serialize_str(s),
+            Value::DateTime(dt) => {
+                serializer.serialize_str(&dt.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+            }
             Value::Boolean(b) => serializer.serialize_bool(*b),
             Value::Number(Number::Integer(i)) => serializer.serialize_i64(*i),
             Value::Number(Number::Float(f)) => serializer.serialize_f64(*f),